@@ -0,0 +1,107 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::Password;
+
+/// Guess scores remembered per candidate set, so identical subproblems met
+/// by different solves are only scored once.
+///
+/// Sets are keyed by their sorted passwords, so two different sets never
+/// share scores. A cache must only be shared by solves using the same
+/// strategy and feedback mode.
+#[derive(Debug, Default)]
+pub struct ScoreCache<const N: usize> {
+    scores: Mutex<HashMap<Vec<Password<N>>, HashMap<Password<N>, f64>>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl<const N: usize> ScoreCache<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The score of every guess of `guesses` against `answer_set`, running
+    /// `score` in parallel for the ones not cached yet.
+    pub fn scores(
+        &self,
+        guesses: &[Password<N>],
+        answer_set: &[Password<N>],
+        score: impl Fn(&Password<N>) -> f64 + Sync,
+    ) -> Vec<f64> {
+        let key = Self::set_key(answer_set);
+
+        let cached = {
+            let scores = self.scores.lock().unwrap();
+            let known = scores.get(&key);
+            guesses
+                .iter()
+                .map(|guess| known.and_then(|known| known.get(guess)).copied())
+                .collect::<Vec<_>>()
+        };
+
+        let missing = guesses
+            .iter()
+            .zip(&cached)
+            .filter(|(_, cached)| cached.is_none())
+            .map(|(guess, _)| guess)
+            .collect::<Vec<_>>();
+        self.hits
+            .fetch_add(guesses.len() - missing.len(), Ordering::Relaxed);
+        self.misses.fetch_add(missing.len(), Ordering::Relaxed);
+
+        let computed = missing
+            .par_iter()
+            .map(|guess| score(guess))
+            .collect::<Vec<_>>();
+
+        let mut computed_scores = computed.iter();
+        let scores = cached
+            .into_iter()
+            .map(|cached| cached.unwrap_or_else(|| *computed_scores.next().unwrap()))
+            .collect();
+
+        if !missing.is_empty() {
+            self.scores
+                .lock()
+                .unwrap()
+                .entry(key)
+                .or_default()
+                .extend(missing.into_iter().cloned().zip(computed));
+        }
+
+        scores
+    }
+
+    /// Fraction of guesses whose score came from the cache, 0.0 if nothing
+    /// was looked up yet.
+    pub fn hit_rate(&self) -> f64 {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let lookups = hits + self.misses.load(Ordering::Relaxed);
+
+        match lookups {
+            0 => 0.0,
+            lookups => hits as f64 / lookups as f64,
+        }
+    }
+
+    /// Number of distinct candidate sets scored.
+    pub fn sets(&self) -> usize {
+        self.scores.lock().unwrap().len()
+    }
+
+    /// Key of `set` that doesn't depend on its order.
+    fn set_key(set: &[Password<N>]) -> Vec<Password<N>> {
+        let mut sorted = set.to_vec();
+        sorted.sort_unstable();
+
+        sorted
+    }
+}
@@ -0,0 +1,120 @@
+use derive_more::Display;
+use strum::EnumString;
+
+use crate::Password;
+
+/// Number of colors in the palette.
+pub const COLORS: usize = 4;
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, EnumString, Display, Debug)]
+pub enum Color {
+    Red,
+    Green,
+    Blue,
+    Yellow,
+}
+
+impl Color {
+    pub const fn index(&self) -> usize {
+        match self {
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Blue => 3,
+            Color::Yellow => 4,
+        }
+    }
+
+    pub const fn abbrev(&self) -> char {
+        match self {
+            Color::Red => 'r',
+            Color::Green => 'g',
+            Color::Blue => 'b',
+            Color::Yellow => 'y',
+        }
+    }
+
+    pub const fn all() -> [Color; COLORS] {
+        [Color::Red, Color::Green, Color::Blue, Color::Yellow]
+    }
+
+    /// The color with the given `index()`.
+    pub fn from_index(index: usize) -> Option<Color> {
+        Color::all()
+            .into_iter()
+            .find(|color| color.index() == index)
+    }
+
+    pub fn from_abbrev(abbrev: char) -> Option<Color> {
+        Color::all()
+            .into_iter()
+            .find(|color| color.abbrev() == abbrev.to_ascii_lowercase())
+    }
+
+    /// The color after this one in `palette`, wrapping around to the first.
+    /// The first color of `palette` if this one isn't in it. Panics if
+    /// `palette` is empty.
+    pub fn next_in(self, palette: &[Color]) -> Color {
+        match palette.iter().position(|color| *color == self) {
+            Some(i) => palette[(i + 1) % palette.len()],
+            None => palette[0],
+        }
+    }
+
+    /// The color before this one in `palette`, wrapping around to the last.
+    /// The last color of `palette` if this one isn't in it. Panics if
+    /// `palette` is empty.
+    pub fn prev_in(self, palette: &[Color]) -> Color {
+        match palette.iter().position(|color| *color == self) {
+            Some(i) => palette[(i + palette.len() - 1) % palette.len()],
+            None => palette[palette.len() - 1],
+        }
+    }
+
+    /// `next_in` the full palette.
+    pub fn next(self) -> Color {
+        self.next_in(&Color::all())
+    }
+
+    /// `prev_in` the full palette.
+    pub fn prev(self) -> Color {
+        self.prev_in(&Color::all())
+    }
+
+    pub fn to_password<const N: usize>(colors: &[Color]) -> Password<N> {
+        Password::new(colors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stepping_around_the_palette_returns_to_the_start() {
+        let palette = [Color::Red, Color::Blue, Color::Yellow];
+
+        for color in Color::all() {
+            let mut next = color;
+            let mut prev = color;
+            for step in 1..=COLORS {
+                next = next.next();
+                prev = prev.prev();
+                assert_eq!(next == color, step == COLORS);
+                assert_eq!(prev == color, step == COLORS);
+            }
+        }
+
+        for color in palette {
+            let mut next = color;
+            let mut prev = color;
+            for step in 1..=palette.len() {
+                next = next.next_in(&palette);
+                prev = prev.prev_in(&palette);
+                assert_eq!(next == color, step == palette.len());
+                assert_eq!(prev == color, step == palette.len());
+            }
+        }
+        assert_eq!(Color::Green.next_in(&palette), Color::Red);
+        assert_eq!(Color::Green.prev_in(&palette), Color::Yellow);
+    }
+}
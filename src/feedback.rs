@@ -0,0 +1,180 @@
+use clap::ValueEnum;
+
+use crate::{Color, COLORS};
+
+/// How much feedback the game gives about a guess.
+///
+/// A hint is passed around as a `usize` bucket index so partitions stay
+/// plain arrays; `decode` turns it back into a `Feedback`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, ValueEnum)]
+pub enum FeedbackMode {
+    /// Number of positions with the correct color
+    #[default]
+    Positional,
+    /// Correct positions plus correct colors in the wrong position, as in Mastermind
+    Full,
+    /// Only whether the guess is exactly right: the hint is either 0 or N
+    Binary,
+    /// Number of positions with the correct color, for each color
+    PerColor,
+}
+
+impl FeedbackMode {
+    /// Number of different hints, i.e. of buckets in a partition.
+    pub const fn hints<const N: usize>(self) -> usize {
+        match self {
+            FeedbackMode::Positional | FeedbackMode::Binary => N + 1,
+            FeedbackMode::Full => (N + 1) * (N + 1),
+            // every count vector, plus the solved hint
+            FeedbackMode::PerColor => (N + 1).pow(COLORS as u32) + 1,
+        }
+    }
+
+    /// The hint given when the guess is the secret.
+    pub const fn solved<const N: usize>(self) -> usize {
+        match self {
+            FeedbackMode::Positional | FeedbackMode::Binary => N,
+            FeedbackMode::Full => Feedback::Full {
+                exact: N,
+                misplaced: 0,
+            }
+            .index::<N>(),
+            FeedbackMode::PerColor => Feedback::PerColor(None).index::<N>(),
+        }
+    }
+
+    pub const fn decode<const N: usize>(self, hint: usize) -> Feedback {
+        match self {
+            FeedbackMode::Positional => Feedback::Positional(hint),
+            FeedbackMode::Full => Feedback::Full {
+                exact: hint / (N + 1),
+                misplaced: hint % (N + 1),
+            },
+            FeedbackMode::Binary => Feedback::Binary(hint == N),
+            FeedbackMode::PerColor if hint == self.solved::<N>() => Feedback::PerColor(None),
+            FeedbackMode::PerColor => {
+                let mut correct = [0; COLORS];
+                let mut rest = hint;
+                let mut color = 0;
+                while color < COLORS {
+                    correct[color] = rest % (N + 1);
+                    rest /= N + 1;
+                    color += 1;
+                }
+
+                Feedback::PerColor(Some(correct))
+            }
+        }
+    }
+
+    /// Reads a hint as typed by a player: the number of hits,
+    /// `exact/misplaced` for full feedback, or the hits of every color
+    /// separated by commas, e.g. `2,0,1,0`, or `solved` for per-color.
+    pub fn parse_hint<const N: usize>(self, hint: &str) -> Result<usize, String> {
+        let parse = |count: &str| count.parse::<usize>().ok().filter(|&count| count <= N);
+
+        match self {
+            FeedbackMode::Positional => {
+                parse(hint).ok_or(format!("hint must be a number from 0 to {N}"))
+            }
+            FeedbackMode::Binary => match parse(hint) {
+                Some(hint) if hint == 0 || hint == N => Ok(hint),
+                _ => Err(format!("hint must be 0 or {N}")),
+            },
+            FeedbackMode::Full => match hint.split_once('/').map(|(e, m)| (parse(e), parse(m))) {
+                Some((Some(exact), Some(misplaced))) if exact + misplaced <= N => {
+                    Ok(Feedback::Full { exact, misplaced }.index::<N>())
+                }
+                _ => Err(format!(
+                    "hint must be \"exact/misplaced\" with at most {N} in total"
+                )),
+            },
+            FeedbackMode::PerColor if hint == "solved" => Ok(self.solved::<N>()),
+            FeedbackMode::PerColor => {
+                let correct = hint
+                    .split(',')
+                    .map(parse)
+                    .collect::<Option<Vec<_>>>()
+                    .and_then(|correct| <[usize; COLORS]>::try_from(correct).ok());
+
+                match correct {
+                    Some(correct) if correct.iter().sum::<usize>() == N => Ok(self.solved::<N>()),
+                    Some(correct) if correct.iter().sum::<usize>() < N => {
+                        Ok(Feedback::PerColor(Some(correct)).index::<N>())
+                    }
+                    _ => Err(format!(
+                        "hint must be {COLORS} comma separated counts with at most {N} in total, or \"solved\""
+                    )),
+                }
+            }
+        }
+    }
+
+    /// Writes a hint the way `parse_hint` reads it back.
+    pub fn format_hint<const N: usize>(self, hint: usize) -> String {
+        match self.decode::<N>(hint) {
+            Feedback::Full { exact, misplaced } => format!("{exact}/{misplaced}"),
+            Feedback::PerColor(Some(correct)) => correct.map(|count| count.to_string()).join(","),
+            Feedback::PerColor(None) => "solved".to_string(),
+            _ => hint.to_string(),
+        }
+    }
+}
+
+/// The feedback given for a guess, decoded from its hint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Feedback {
+    Positional(usize),
+    Full {
+        exact: usize,
+        misplaced: usize,
+    },
+    Binary(bool),
+    /// Hits of every color in the order of `Color::all()`, or `None` when
+    /// the guess is the secret, whose hits are just the guess's colors.
+    PerColor(Option<[usize; COLORS]>),
+}
+
+impl Feedback {
+    /// The hint this feedback is stored as; the inverse of
+    /// `FeedbackMode::decode`.
+    pub const fn index<const N: usize>(self) -> usize {
+        match self {
+            Feedback::Positional(correct) => correct,
+            Feedback::Full { exact, misplaced } => exact * (N + 1) + misplaced,
+            Feedback::Binary(true) => N,
+            Feedback::Binary(false) => 0,
+            Feedback::PerColor(None) => (N + 1).pow(COLORS as u32),
+            Feedback::PerColor(Some(correct)) => {
+                let mut index = 0;
+                let mut color = COLORS;
+                while color > 0 {
+                    color -= 1;
+                    index = index * (N + 1) + correct[color];
+                }
+
+                index
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Feedback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Feedback::Positional(correct) => write!(f, "{correct} hits"),
+            Feedback::Full { exact, misplaced } => write!(f, "{exact} exact {misplaced} misplaced"),
+            Feedback::Binary(true) => write!(f, "solved"),
+            Feedback::Binary(false) => write!(f, "wrong"),
+            Feedback::PerColor(Some(correct)) => {
+                let hits = Color::all()
+                    .iter()
+                    .zip(correct)
+                    .map(|(color, count)| format!("{}{count}", color.abbrev()))
+                    .collect::<Vec<_>>();
+                write!(f, "{}", hits.join(" "))
+            }
+            Feedback::PerColor(None) => write!(f, "solved"),
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use std::str::FromStr;
+
+use derive_more::Display;
+
+#[derive(Debug, Display)]
+#[display(fmt = "invalid histogram: {}", _0)]
+pub struct HistogramError(String);
+
+impl std::error::Error for HistogramError {}
+
+/// How many guesses each solved problem took, for the whole or a part of a
+/// `solve_all` run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Histogram {
+    /// Number of problems solved in each number of guesses, indexed by it.
+    counts: Vec<usize>,
+    /// `(problem index, guesses)` of the problem that took the most
+    /// guesses, the highest index among ties.
+    worst_case: Option<(usize, usize)>,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds problem `index`, solved in `guesses` guesses.
+    pub fn record(&mut self, index: usize, guesses: usize) {
+        if self.counts.len() <= guesses {
+            self.counts.resize(guesses + 1, 0);
+        }
+        self.counts[guesses] += 1;
+
+        if self
+            .worst_case
+            .is_none_or(|worst| (guesses, index) > (worst.1, worst.0))
+        {
+            self.worst_case = Some((index, guesses));
+        }
+    }
+
+    /// The histogram of both runs together. Merging the parts of a run gives
+    /// the same histogram as the whole run, in any order.
+    pub fn merge(&self, other: &Self) -> Self {
+        let mut counts = vec![0; self.counts.len().max(other.counts.len())];
+        for (merged, count) in counts.iter_mut().zip(&self.counts) {
+            *merged += count;
+        }
+        for (merged, count) in counts.iter_mut().zip(&other.counts) {
+            *merged += count;
+        }
+
+        let worst_case = match (self.worst_case, other.worst_case) {
+            (Some(a), Some(b)) => Some(if (a.1, a.0) > (b.1, b.0) { a } else { b }),
+            (a, b) => a.or(b),
+        };
+
+        Self { counts, worst_case }
+    }
+
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    pub fn problems(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    pub fn total_guesses(&self) -> usize {
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(guesses, count)| guesses * count)
+            .sum()
+    }
+
+    /// Average guesses per problem, 0.0 if there are none.
+    pub fn average(&self) -> f64 {
+        match self.problems() {
+            0 => 0.0,
+            problems => self.total_guesses() as f64 / problems as f64,
+        }
+    }
+
+    /// Middle number of guesses, the mean of the two middle ones for an even
+    /// number of problems. 0.0 if there are none. Found by walking the
+    /// counts, so nothing is sorted however many problems there are.
+    pub fn median(&self) -> f64 {
+        let problems = self.problems();
+        if problems == 0 {
+            return 0.0;
+        }
+
+        // guesses of the problems ranked `low` and `high`, counting from 0
+        let (low, high) = ((problems - 1) / 2, problems / 2);
+        let mut seen = 0;
+        let mut low_guesses = None;
+        for (guesses, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen > low && low_guesses.is_none() {
+                low_guesses = Some(guesses);
+            }
+            if seen > high {
+                return (low_guesses.unwrap() + guesses) as f64 / 2.0;
+            }
+        }
+
+        unreachable!("the counts add up to more than `high`")
+    }
+
+    /// Population standard deviation of the guesses per problem, 0.0 if
+    /// there are none. Welford's update, weighted by the count of each
+    /// number of guesses, keeps it precise without summing large squares.
+    pub fn stddev(&self) -> f64 {
+        let mut seen = 0;
+        let mut mean = 0.0;
+        let mut squares = 0.0;
+        for (guesses, &count) in self.counts.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+
+            seen += count;
+            let delta = guesses as f64 - mean;
+            mean += delta * count as f64 / seen as f64;
+            squares += delta * count as f64 * (guesses as f64 - mean);
+        }
+
+        match seen {
+            0 => 0.0,
+            seen => (squares / seen as f64).sqrt(),
+        }
+    }
+
+    /// Fraction of problems solved in at most `guesses` guesses, 0.0 if
+    /// there are none.
+    pub fn solved_within(&self, guesses: usize) -> f64 {
+        match self.problems() {
+            0 => 0.0,
+            problems => {
+                let solved = self.counts.iter().take(guesses + 1).sum::<usize>();
+                solved as f64 / problems as f64
+            }
+        }
+    }
+
+    pub fn worst_case(&self) -> Option<(usize, usize)> {
+        self.worst_case
+    }
+}
+
+impl FromIterator<(usize, usize)> for Histogram {
+    fn from_iter<I: IntoIterator<Item = (usize, usize)>>(iter: I) -> Self {
+        let mut histogram = Self::new();
+        for (index, guesses) in iter {
+            histogram.record(index, guesses);
+        }
+
+        histogram
+    }
+}
+
+/// A `worst <index> <guesses>` line followed by a `<guesses> <count>` line
+/// for every number of guesses that occurred.
+impl std::fmt::Display for Histogram {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some((index, guesses)) = self.worst_case {
+            writeln!(f, "worst {index} {guesses}")?;
+        }
+
+        for (guesses, count) in self.counts.iter().enumerate() {
+            if *count > 0 {
+                writeln!(f, "{guesses} {count}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Histogram {
+    type Err = HistogramError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut histogram = Self::new();
+
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let numbers = line
+                .trim_start_matches("worst ")
+                .split_whitespace()
+                .map(str::parse::<usize>)
+                .collect::<Result<Vec<_>, _>>();
+
+            match (line.starts_with("worst "), numbers.as_deref()) {
+                (true, Ok(&[index, guesses])) => histogram.worst_case = Some((index, guesses)),
+                (false, Ok(&[guesses, count])) => {
+                    if histogram.counts.len() <= guesses {
+                        histogram.counts.resize(guesses + 1, 0);
+                    }
+                    histogram.counts[guesses] += count;
+                }
+                _ => return Err(HistogramError(format!("unexpected line '{line}'"))),
+            }
+        }
+
+        if histogram.worst_case.is_none() != (histogram.problems() == 0) {
+            return Err(HistogramError(
+                "the worst case must be given exactly when there are problems".to_string(),
+            ));
+        }
+
+        Ok(histogram)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(problem index, guesses)` of a made-up run, with ties for the worst.
+    fn solves() -> Vec<(usize, usize)> {
+        (0..100).map(|i| (i, 2 + (i * 7) % 5)).collect()
+    }
+
+    #[test]
+    fn merged_parts_equal_the_whole() {
+        let solves = solves();
+        let whole = solves.iter().copied().collect::<Histogram>();
+
+        for (a, b) in [(0, 100), (30, 60), (1, 99), (50, 50)] {
+            let parts = [&solves[..a], &solves[a..b], &solves[b..]]
+                .map(|part| part.iter().copied().collect::<Histogram>());
+
+            assert_eq!(parts[0].merge(&parts[1]).merge(&parts[2]), whole);
+            assert_eq!(parts[2].merge(&parts[0]).merge(&parts[1]), whole);
+            assert_eq!(parts[1].merge(&parts[2].merge(&parts[0])), whole);
+        }
+
+        assert_eq!(whole.merge(&Histogram::new()), whole);
+        assert_eq!(whole.problems(), 100);
+        assert_eq!(whole.worst_case(), Some((97, 6)));
+    }
+
+    #[test]
+    fn display_round_trips_through_from_str() {
+        let whole = solves().into_iter().collect::<Histogram>();
+        assert_eq!(whole.to_string().parse::<Histogram>().unwrap(), whole);
+
+        let empty = Histogram::new();
+        assert_eq!(empty.to_string(), "");
+        assert_eq!("".parse::<Histogram>().unwrap(), empty);
+    }
+
+    #[test]
+    fn from_str_rejects_malformed_histograms() {
+        assert!("3 4".parse::<Histogram>().is_err());
+        assert!("worst 1 3".parse::<Histogram>().is_err());
+        assert!("worst 1 3\n3 x".parse::<Histogram>().is_err());
+        assert!("worst 1 3\n3 4 5".parse::<Histogram>().is_err());
+    }
+
+    /// The histogram of a run where problem `i` took `guesses[i]` guesses.
+    fn of(guesses: &[usize]) -> Histogram {
+        guesses.iter().copied().enumerate().collect()
+    }
+
+    #[test]
+    fn median_and_stddev_of_known_data() {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-12;
+
+        let odd = of(&[7, 3, 2, 5, 3]);
+        assert_eq!(odd.median(), 3.0);
+        assert!(close(odd.stddev(), 3.2f64.sqrt()));
+
+        let even = of(&[7, 3, 2, 5]);
+        assert_eq!(even.median(), 4.0);
+        assert!(close(even.stddev(), 3.6875f64.sqrt()));
+
+        let single = of(&[4, 4, 4]);
+        assert_eq!(single.median(), 4.0);
+        assert_eq!(single.stddev(), 0.0);
+
+        let empty = Histogram::new();
+        assert_eq!(empty.median(), 0.0);
+        assert_eq!(empty.stddev(), 0.0);
+    }
+}
@@ -0,0 +1,36 @@
+mod cache;
+mod color;
+mod feedback;
+mod histogram;
+mod optimal;
+mod oracle;
+mod password;
+mod report;
+mod set;
+mod solver;
+mod strategy;
+mod transcript;
+mod tree;
+mod weights;
+
+pub use cache::ScoreCache;
+pub use color::{Color, COLORS};
+pub use feedback::{Feedback, FeedbackMode};
+pub use histogram::{Histogram, HistogramError};
+pub use optimal::optimal_total_guesses;
+pub use oracle::{Adversary, KnownSecret, Liar, Oracle, Scripted};
+pub use password::{Password, PasswordBuilder, PasswordError, Reveal};
+pub use report::SolveReport;
+pub use set::PasswordSet;
+pub use solver::{
+    choose_guess, choose_guess_cached, filter_candidates, filter_with_lies,
+    partition_representatives, score_guesses, solve_automatically, MultiSolver, SolveOptions,
+    SolvePath, SolveStep, Solver, SolverConfig, TieBreak,
+};
+pub use strategy::{
+    entropy, expected_gain, expected_remaining, Adaptive, Entropy, ExpectedRemaining,
+    GuessStrategy, Minimax, MinimaxEntropy, WeightedEntropy,
+};
+pub use transcript::{Discrepancy, Transcript, TranscriptError};
+pub use tree::DecisionTree;
+pub use weights::ColorWeights;
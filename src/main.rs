@@ -1,274 +1,1882 @@
-use std::{collections::HashMap, sync::OnceLock};
+use std::{
+    cmp::Reverse,
+    collections::HashSet,
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufWriter, IsTerminal, Write},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex, OnceLock,
+    },
+    time::Duration,
+};
 
-use clap::Parser;
-use derive_more::Display;
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
 use itertools::Itertools;
-use rand::{thread_rng, Fill, Rng};
+use oneshot_password::{
+    choose_guess, entropy, expected_remaining, filter_candidates, optimal_total_guesses,
+    solve_automatically, Adaptive, Adversary, Color, ColorWeights, DecisionTree, Entropy,
+    ExpectedRemaining, Feedback, FeedbackMode, GuessStrategy, Histogram, KnownSecret, Liar,
+    Minimax, MinimaxEntropy, Oracle, Password, PasswordSet, Reveal, ScoreCache, SolveOptions,
+    SolvePath, SolveReport, Solver, SolverConfig, TieBreak, Transcript, WeightedEntropy, COLORS,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use rayon::prelude::{
     IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
 };
-use strum::EnumString;
 
 const PASSWORD_LENGTH: usize = 5;
 type Problem = Password<PASSWORD_LENGTH>;
 static PROBLEM_SET: OnceLock<Vec<Problem>> = OnceLock::new();
 
-#[derive(Copy, Clone, PartialEq, Eq, EnumString, Display, Debug)]
-enum Color {
-    Red,
-    Green,
-    Blue,
-    Yellow,
+/// Reads one `[guess] hint` line of the assist mode. Without a guess, the
+/// hint is for the recommended guess.
+fn parse_assist_line(
+    line: &str,
+    recommended: &Problem,
+    feedback: FeedbackMode,
+) -> Result<(Problem, usize), String> {
+    let (guess, hint) = match line.split_whitespace().collect_vec()[..] {
+        [hint] => (recommended.clone(), hint),
+        [guess, hint] => (guess.parse().map_err(|err| format!("{err}"))?, hint),
+        _ => return Err("expected \"[guess] hint\"".to_string()),
+    };
+
+    Ok((guess, feedback.parse_hint::<PASSWORD_LENGTH>(hint)?))
 }
 
-impl Color {
-    const fn index(&self) -> usize {
-        match self {
-            Color::Red => 1,
-            Color::Green => 2,
-            Color::Blue => 3,
-            Color::Yellow => 4,
+/// Recommends guesses for a game played elsewhere, reading `[guess] hint`
+/// lines from stdin until the secret is found or stdin ends.
+///
+/// When stdin isn't a terminal, nothing is prompted and only the final
+/// result is printed, so a game can be replayed with `echo` or `cat`.
+fn assist_solving<S: GuessStrategy<PASSWORD_LENGTH> + Clone>(
+    problem_set: &[Problem],
+    candidates: &[Problem],
+    strategy: &S,
+    options: SolveOptions,
+    color_probabilities: bool,
+) -> io::Result<()> {
+    let interactive = io::stdin().is_terminal();
+    let mut solver = Solver::with_candidates(
+        problem_set.to_vec(),
+        candidates.to_vec(),
+        strategy.clone(),
+        options,
+    );
+    let mut lines = io::stdin().lock().lines();
+
+    loop {
+        if let Some(solution) = solver.solution() {
+            println!("Solution: {}", solution.abbrev());
+            return Ok(());
+        }
+
+        let remaining = solver.remaining_candidates(&[]);
+        let Some(recommended) = solver.next_guess() else {
+            println!("No candidate matches every hint given");
+            return Ok(());
+        };
+
+        if interactive {
+            println!(
+                "{} candidates left, try {}",
+                remaining.len(),
+                recommended.abbrev()
+            );
+            println!(
+                "colors left per position: {}",
+                Problem::most_informative_positions(&remaining)
+                    .iter()
+                    .join(" ")
+            );
+            if color_probabilities {
+                print_color_probabilities(&remaining);
+            }
+            print!("[guess] hint> ");
+            io::stdout().flush()?;
+        }
+
+        let Some(line) = lines.next().transpose()? else {
+            if interactive {
+                println!();
+            }
+            println!(
+                "{} candidates left, next guess: {}",
+                remaining.len(),
+                recommended.abbrev()
+            );
+            return Ok(());
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match parse_assist_line(&line, &recommended, options.feedback) {
+            Ok((guess, hint)) => solver.apply(&guess, hint),
+            Err(err) => eprintln!("error: {err}"),
         }
     }
+}
+
+/// Lets the player guess a random secret among `candidates`, printing the
+/// feedback for every password read from stdin until it is found. The
+/// secret is revealed if stdin ends first. The game is written to
+/// `transcript` either way.
+fn play(
+    candidates: &[Problem],
+    feedback: FeedbackMode,
+    transcript: Option<&Path>,
+) -> io::Result<()> {
+    let Some(secret) = candidates.choose(&mut rand::thread_rng()) else {
+        println!("No candidate to play with");
+        return Ok(());
+    };
+    let interactive = io::stdin().is_terminal();
+    let mut lines = io::stdin().lock().lines();
+    let mut moves = vec![];
+
+    loop {
+        if interactive {
+            print!("guess> ");
+            io::stdout().flush()?;
+        }
+
+        let Some(line) = lines.next().transpose()? else {
+            if interactive {
+                println!();
+            }
+            println!("The secret was {}", secret.abbrev());
+            break;
+        };
 
-    const fn abbrev(&self) -> char {
-        match self {
-            Color::Red => 'r',
-            Color::Green => 'g',
-            Color::Blue => 'b',
-            Color::Yellow => 'y',
+        if line.trim().is_empty() {
+            continue;
         }
+
+        let guess = match line.trim().parse::<Problem>() {
+            Ok(guess) => guess,
+            Err(err) => {
+                eprintln!("error: {err}");
+                continue;
+            }
+        };
+        let hint = secret.hint(&guess, feedback);
+        moves.push((guess.clone(), hint));
+
+        if guess == *secret {
+            println!("Solved in {} guesses", moves.len());
+            break;
+        }
+        println!("{}", feedback.decode::<PASSWORD_LENGTH>(hint));
     }
 
-    const fn all() -> [Color; 4] {
-        [Color::Red, Color::Green, Color::Blue, Color::Yellow]
+    if let Some(path) = transcript {
+        let transcript = Transcript {
+            secret: secret.clone(),
+            feedback,
+            moves,
+        };
+        std::fs::write(path, transcript.to_string())?;
     }
 
-    pub fn to_password(colors: &[Color]) -> Problem {
-        Password::new(colors)
+    Ok(())
+}
+
+/// Replays the game recorded at `path`, printing every guess whose
+/// recorded hint isn't the one its secret gives. `Ok(false)` if there was
+/// any.
+fn replay(path: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+    let transcript = std::fs::read_to_string(path)?.parse::<Transcript<PASSWORD_LENGTH>>()?;
+    let discrepancies = transcript.replay();
+
+    for discrepancy in &discrepancies {
+        println!(
+            "guess {} ({}): recorded {}, but the secret gives {}",
+            discrepancy.turn + 1,
+            discrepancy.guess.abbrev(),
+            transcript
+                .feedback
+                .decode::<PASSWORD_LENGTH>(discrepancy.recorded),
+            transcript
+                .feedback
+                .decode::<PASSWORD_LENGTH>(discrepancy.actual)
+        );
     }
+
+    println!(
+        "Replayed {} guesses for {}: {} discrepancies, {}",
+        transcript.moves.len(),
+        transcript.secret.abbrev(),
+        discrepancies.len(),
+        if transcript.is_solved() {
+            "solved"
+        } else {
+            "unsolved"
+        }
+    );
+
+    Ok(discrepancies.is_empty())
 }
 
-impl<const N: usize> Fill for Password<N> {
-    fn try_fill<R: rand::Rng + ?Sized>(&mut self, rng: &mut R) -> Result<(), rand::Error> {
-        let mut i = 0;
-        while i < N {
-            let result = rng.gen_range(0.0..=1.0);
+/// Completed `solve_all` results, appended as `index attempts` lines so an
+/// interrupted run can be resumed with the same options.
+struct Checkpoint {
+    file: Mutex<File>,
+}
 
-            self.answer[i] = if (0.0..=0.25).contains(&result) {
-                Color::Red
-            } else if (0.25..=0.50).contains(&result) {
-                Color::Green
-            } else if (0.50..=0.75).contains(&result) {
-                Color::Blue
-            } else {
-                Color::Yellow
-            };
-            i += 1;
+impl Checkpoint {
+    /// Opens the checkpoint at `path`, returning the results already stored
+    /// in it when resuming. Otherwise the file is started over.
+    fn open(path: &Path, resume: bool) -> io::Result<(Self, Vec<(usize, usize)>)> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) if resume => contents,
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => String::new(),
+        };
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(resume)
+            .write(true)
+            .truncate(!resume)
+            .open(path)?;
+
+        // terminate a cut-off line so it doesn't swallow the next record
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            writeln!(file)?;
         }
 
-        Ok(())
+        Ok((
+            Self {
+                file: Mutex::new(file),
+            },
+            Self::parse(&contents),
+        ))
     }
-}
 
-#[derive(Clone)]
-pub struct Password<const N: usize> {
-    answer: [Color; N],
+    fn parse(contents: &str) -> Vec<(usize, usize)> {
+        contents
+            .split_inclusive('\n')
+            // a line without its newline was cut off mid-write and is solved again
+            .filter(|line| line.ends_with('\n'))
+            .filter_map(|line| {
+                let (index, attempts) = line.trim().split_once(' ')?;
+                Some((index.parse().ok()?, attempts.parse().ok()?))
+            })
+            .collect()
+    }
+
+    fn record(&self, index: usize, attempts: usize) -> io::Result<()> {
+        let mut file = self.file.lock().unwrap();
+        writeln!(file, "{index} {attempts}")?;
+        file.flush()
+    }
 }
 
-impl<const N: usize> std::fmt::Display for Password<{ N }> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self.answer)
+/// The oracle holding `secret`, lying up to --lies times with --tell-lies.
+fn secret_oracle(
+    secret: &Problem,
+    options: &SolveOptions,
+    solver: &SolverArgs,
+) -> Box<dyn Oracle<PASSWORD_LENGTH>> {
+    if solver.tell_lies {
+        Box::new(Liar::new(
+            secret.clone(),
+            options.feedback,
+            options.lies,
+            options.seed,
+        ))
+    } else {
+        Box::new(KnownSecret {
+            secret: secret.clone(),
+            feedback: options.feedback,
+        })
     }
 }
 
-impl<const N: usize> Password<{ N }> {
-    fn generate() -> Self {
-        let answer = [Color::Red; N];
-        let mut password = Self { answer };
-        thread_rng().fill(&mut password);
+fn solve_all<S: GuessStrategy<PASSWORD_LENGTH>>(
+    problem_set: &[Problem],
+    candidates: &[Problem],
+    strategy: &S,
+    options: SolveOptions,
+    solver: &SolverArgs,
+    args: &SolveAllArgs,
+) -> io::Result<SolveReport<PASSWORD_LENGTH>> {
+    let (checkpoint, mut tries) = match &args.checkpoint {
+        Some(path) => {
+            let (checkpoint, completed) = Checkpoint::open(path, args.resume)?;
+            (Some(checkpoint), completed)
+        }
+        None => (None, vec![]),
+    };
 
-        password
+    if let Some((index, _)) = tries.iter().find(|(i, _)| *i >= candidates.len()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "checkpoint has problem #{index}, but there are only {} problems",
+                candidates.len()
+            ),
+        ));
     }
 
-    fn new(comb: &[Color]) -> Password<N> {
-        let mut answer = [Color::Red; N];
+    if let Some(index) = args.trace_index.filter(|&i| i >= candidates.len()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "can't trace problem #{index}, there are only {} problems",
+                candidates.len()
+            ),
+        ));
+    }
 
-        for i in 0..N {
-            answer[i] = comb[i];
-        }
+    if !tries.is_empty() {
+        println!("Resuming with {} problems already solved", tries.len());
+    }
+    let completed = tries.iter().map(|(i, _)| *i).collect::<HashSet<_>>();
 
-        Self { answer }
+    let mut order = (0..candidates.len())
+        .filter(|i| !completed.contains(i))
+        .collect_vec();
+    if args.deterministic_all_order {
+        // secrets sharing the opener's hint take the same second guess
+        let opener = choose_guess(problem_set, candidates, strategy, &options);
+        order.sort_by_key(|&i| (candidates[i].hint(&opener, options.feedback), i));
+    }
+    if args.shuffle {
+        order.shuffle(&mut StdRng::seed_from_u64(options.seed));
     }
 
-    fn check_answer(&self, answer: &Password<N>) -> usize {
-        let mut correct = 0;
+    let cache = solver.cache_scores.then(ScoreCache::new);
+
+    catch_interrupt();
+
+    // do it for every possible case
+    let solved = order
+        .into_par_iter()
+        .map(|i| {
+            // in-flight solves finish, so everything recorded is complete
+            if INTERRUPTED.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
+
+            let options = SolveOptions {
+                print_steps: false,
+                ..options.for_problem(i)
+            };
+            let path = solve_automatically(
+                problem_set,
+                candidates,
+                &mut *secret_oracle(&candidates[i], &options, solver),
+                strategy,
+                options,
+                cache.as_ref(),
+            );
+
+            // printed at once after the solve, so nothing can come in between
+            if args.trace_index == Some(i) {
+                print!("Tracing problem #{i} ({})\n{path}", candidates[i].abbrev());
+            }
 
-        for i in 0..N {
-            if self.answer[i] == answer.answer[i] {
-                correct += 1;
+            if path.solution().is_none() {
+                eprintln!(
+                    "warning: problem #{i} ({}) stalled at guess {}",
+                    candidates[i].abbrev(),
+                    path.guesses()
+                );
             }
+
+            if let Some(checkpoint) = &checkpoint {
+                checkpoint.record(i, path.guesses())?;
+            }
+
+            println!("Solved problem #{i}");
+            Ok(Some((i, path)))
+        })
+        .collect::<io::Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect_vec();
+
+    let interrupted = INTERRUPTED.load(Ordering::Relaxed);
+    if interrupted {
+        println!(
+            "Interrupted with {} of {} problems solved",
+            tries.len() + solved.len(),
+            candidates.len()
+        );
+    }
+
+    if args.verify_all {
+        let failed = solved
+            .iter()
+            .filter(|(i, path)| path.solution() != Some(&candidates[*i]))
+            .inspect(|(i, path)| {
+                let found = path
+                    .solution()
+                    .map_or("nothing".to_string(), Password::abbrev);
+                eprintln!(
+                    "error: problem #{i} ({}) ended on {found}",
+                    candidates[*i].abbrev()
+                );
+            })
+            .count();
+        if failed > 0 {
+            return Err(io::Error::other(format!(
+                "{failed} of {} secrets weren't isolated",
+                solved.len()
+            )));
         }
+        println!("Verified: every secret was isolated");
+    }
 
-        correct
+    if let Some(csv) = &args.detailed_csv {
+        write_detailed_csv(csv, &solved)?;
+    }
+    if let Some(csv) = &args.entropy_decay {
+        write_entropy_decay(csv, &solved)?;
     }
 
-    pub fn matches_description(&self, description: &Password<N>, hint: usize) -> bool {
-        self.check_answer(description) == hint
+    if let Some(cache) = &cache {
+        println!(
+            "Score cache: {:.1}% hits over {} candidate sets",
+            cache.hit_rate() * 100.0,
+            cache.sets()
+        );
     }
 
-    pub fn calculate_entropy<'a>(
-        &self,
-        answer_set: &'a [Password<N>],
-    ) -> (f64, HashMap<usize, Vec<&'a Password<N>>>) {
-        let mut answer_map = HashMap::new();
+    tries.extend(solved.iter().map(|(i, path)| (*i, path.guesses())));
+    if let Some(path) = &args.rank_difficulty {
+        write_difficulty_ranking(path, candidates, &tries)?;
+    }
+    let histogram = tries.into_iter().collect::<Histogram>();
 
-        for ans in answer_set {
-            let hints = self.check_answer(ans);
+    if let Some(path) = &args.histogram {
+        std::fs::write(path, histogram.to_string())?;
+    }
 
-            answer_map.entry(hints).or_insert(vec![]).push(ans);
-        }
+    Ok(SolveReport::new(histogram, candidates))
+}
 
-        let entropy = answer_map
-            .par_iter()
-            .map(|(_, v)| -f64::log2(v.len() as f64 / answer_set.len() as f64))
-            .sum();
+/// Set once Ctrl-C is pressed after `catch_interrupt`.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Exit code of a `solve_all` run stopped by Ctrl-C: 128 + SIGINT, as
+/// shells report a process killed by it.
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Makes the first Ctrl-C set `INTERRUPTED` instead of killing the process.
+/// A second one kills it as usual.
+#[cfg(unix)]
+fn catch_interrupt() {
+    extern "C" fn on_interrupt(_: libc::c_int) {
+        INTERRUPTED.store(true, Ordering::Relaxed);
+        // SAFETY: signal is async-signal-safe
+        unsafe { libc::signal(libc::SIGINT, libc::SIG_DFL) };
+    }
+
+    // SAFETY: the handler only touches an atomic and calls signal
+    unsafe {
+        libc::signal(
+            libc::SIGINT,
+            on_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t,
+        )
+    };
+}
+
+#[cfg(not(unix))]
+fn catch_interrupt() {}
 
-        (entropy, answer_map)
+fn print_summary(report: &SolveReport<PASSWORD_LENGTH>) {
+    println!("Average: {}", report.average);
+    println!(
+        "Median: {} | Standard deviation: {:.4}",
+        report.median, report.stddev
+    );
+    if let Some((problem, tries)) = &report.worst_case {
+        println!("Worst Case: {problem} | {tries} tries");
+        print_solved_curve(*tries, |guesses| report.histogram.solved_within(guesses));
     }
 }
 
-fn solve_automatically(
+/// Prints the percentage of secrets solved within each number of guesses
+/// up to `max_guesses`.
+fn print_solved_curve(max_guesses: usize, solved_within: impl Fn(usize) -> f64) {
+    println!("\nguesses | solved");
+    for guesses in 1..=max_guesses {
+        println!("{guesses:>7} | {:>6.2}%", solved_within(guesses) * 100.0);
+    }
+}
+
+/// Solves `args.count` secrets drawn at random from `candidates` with
+/// --seed, optionally appending one CSV row per trial to --append-results.
+/// With --repeat, runs that many batches, each drawn from its own seed, and
+/// reports the mean of their averages with a 95% confidence interval.
+fn solve_trials<S: GuessStrategy<PASSWORD_LENGTH>>(
     problem_set: &[Problem],
-    solution: Problem,
-    print_steps: bool,
-) -> Vec<Problem> {
-    let mut answer_set = problem_set.to_vec();
-    let mut answers = vec![];
-    while answer_set.len() > 1 {
-        let (answer, (_, mut distribution)) = problem_set
+    candidates: &[Problem],
+    strategy: &S,
+    options: SolveOptions,
+    solver: &SolverArgs,
+    args: &TrialsArgs,
+) -> io::Result<()> {
+    let cache = solver.cache_scores.then(ScoreCache::new);
+
+    let mut batches = vec![];
+    for batch in 0..args.repeat.get() {
+        // every trial of every batch gets its own seed
+        let seed = options.seed.wrapping_add((batch * args.count) as u64);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let secrets = (0..args.count)
+            .map(|_| rng.gen_range(0..candidates.len()))
+            .collect_vec();
+
+        let solved = secrets
             .par_iter()
-            .map(|comb| (comb.clone(), comb.calculate_entropy(&answer_set)))
-            .max_by(|(_, (entropy_a, _)), (_, (entropy_b, _))| entropy_a.total_cmp(entropy_b))
-            .unwrap();
+            .enumerate()
+            .map(|(trial, &i)| {
+                let options = SolveOptions {
+                    print_steps: false,
+                    seed: seed.wrapping_add(trial as u64),
+                    ..options
+                };
+                let path = solve_automatically(
+                    problem_set,
+                    candidates,
+                    &mut *secret_oracle(&candidates[i], &options, solver),
+                    strategy,
+                    options,
+                    cache.as_ref(),
+                );
+
+                (i, path.guesses())
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(path) = &args.append_results {
+            append_results(path, seed, candidates, &solved)?;
+        }
+
+        batches.push((seed, solved.into_iter().collect::<Histogram>()));
+    }
+
+    if args.repeat.get() > 1 {
+        println!("batch |                 seed | average | worst case");
+        for (batch, (seed, histogram)) in batches.iter().enumerate() {
+            let worst = histogram.worst_case().map_or(0, |(_, guesses)| guesses);
+            println!(
+                "{batch:>5} | {seed:>20} | {:>7.4} | {worst:>10}",
+                histogram.average()
+            );
+        }
+
+        let averages = batches
+            .iter()
+            .map(|(_, histogram)| histogram.average())
+            .collect_vec();
+        let (mean, half_width) = confidence_interval(&averages);
+        println!("\nMean of batch averages: {mean:.4} ± {half_width:.4} (95% confidence)\n");
+    }
 
-        let hint = solution.check_answer(&answer);
+    let pooled = batches
+        .iter()
+        .fold(Histogram::new(), |pooled, (_, histogram)| {
+            pooled.merge(histogram)
+        });
+    print_summary(&SolveReport::new(pooled, candidates));
+
+    Ok(())
+}
+
+/// Mean of `samples` and the half width of its 95% confidence interval,
+/// from Student's t distribution. The half width is infinite for fewer
+/// than two samples.
+fn confidence_interval(samples: &[f64]) -> (f64, f64) {
+    // two-sided 97.5% quantiles for 1 to 30 degrees of freedom
+    const T_975: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
 
-        answer_set = distribution
-            .remove(&hint)
-            .unwrap()
+    let n = samples.len();
+    let mean = samples.iter().sum::<f64>() / n as f64;
+    if n < 2 {
+        return (mean, f64::INFINITY);
+    }
+
+    let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+    let t = T_975.get(n - 2).copied().unwrap_or(1.960);
+
+    (mean, t * (variance / n as f64).sqrt())
+}
+
+/// Solves `count` secrets drawn from `weights` with --seed, with
+/// `WeightedEntropy` once under a uniform prior and once knowing the
+/// weights, and prints both summaries. Only the prior differs between the
+/// two, so the difference is what assuming a uniform prior costs.
+fn prior_cost(
+    problem_set: &[Problem],
+    candidates: &[Problem],
+    options: SolveOptions,
+    solver: &SolverArgs,
+    weights: &ColorWeights<PASSWORD_LENGTH>,
+    count: usize,
+) {
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let secrets = (0..count)
+        .map(|_| {
+            let secret = (0..PASSWORD_LENGTH).fold(Password::builder(), |builder, position| {
+                builder.set(position, weights.sample(position, &mut rng))
+            });
+            let secret = secret.build();
+            match &solver.reveal {
+                Some(reveal) => reveal.apply(secret),
+                None => secret,
+            }
+        })
+        .collect_vec();
+
+    let options = SolveOptions {
+        print_steps: false,
+        ..options
+    };
+    let solve_secrets = |strategy: &(dyn Fn(&mut dyn Oracle<PASSWORD_LENGTH>) -> usize + Sync)| {
+        secrets
+            .par_iter()
+            .map(|secret| {
+                let index = candidates.iter().position(|candidate| candidate == secret);
+                let guesses = strategy(&mut *secret_oracle(secret, &options, solver));
+                (index.unwrap(), guesses)
+            })
+            .collect::<Vec<_>>()
             .into_iter()
-            .cloned()
-            .collect::<Vec<_>>();
+            .collect::<Histogram>()
+    };
+
+    let solve_with = |weights: ColorWeights<PASSWORD_LENGTH>| {
+        let strategy = WeightedEntropy { weights };
+        solve_secrets(&|oracle| {
+            solve_automatically(problem_set, candidates, oracle, &strategy, options, None).guesses()
+        })
+    };
+    let uniform = solve_with(ColorWeights::uniform());
+    let weighted = solve_with(weights.clone());
+
+    println!("prior    | average | worst case");
+    for (prior, histogram) in [("uniform", &uniform), ("weighted", &weighted)] {
+        let worst = histogram.worst_case().map_or(0, |(_, guesses)| guesses);
+        println!("{prior:<8} | {:>7.4} | {worst:>10}", histogram.average());
+    }
+    println!(
+        "\nAssuming a uniform prior costs {:+.4} guesses per secret",
+        uniform.average() - weighted.average()
+    );
+}
 
-        answers.push(answer.clone());
+/// Appends a `seed,trial,secret,guesses` row per trial to the CSV at
+/// `path`, writing the header only if the file is new or empty. The file is
+/// locked while writing so concurrent runs don't interleave rows.
+fn append_results(
+    path: &Path,
+    seed: u64,
+    candidates: &[Problem],
+    solved: &[(usize, usize)],
+) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.lock()?;
 
-        if print_steps {
-            println!("=== {} ===", answer);
-            println!("{} hits | {} remaining", hint, answer_set.len())
+    let mut rows = String::new();
+    if file.metadata()?.len() == 0 {
+        rows.push_str("seed,trial,secret,guesses\n");
+    }
+    for (trial, (i, guesses)) in solved.iter().enumerate() {
+        rows.push_str(&format!(
+            "{seed},{trial},{},{guesses}\n",
+            candidates[*i].abbrev()
+        ));
+    }
+
+    (&file).write_all(rows.as_bytes())?;
+    file.unlock()
+}
+
+/// Merges the histograms of partial solve-all runs stored at `paths`.
+fn merge_histograms(paths: &[PathBuf]) -> Result<Histogram, Box<dyn std::error::Error>> {
+    paths.iter().try_fold(Histogram::new(), |merged, path| {
+        let part = std::fs::read_to_string(path)?.parse::<Histogram>()?;
+        Ok(merged.merge(&part))
+    })
+}
+
+/// Prints a row per position with the percentage of `candidates` having
+/// each color there.
+fn print_color_probabilities(candidates: &[Problem]) {
+    println!(
+        "position | {}",
+        Color::all()
+            .iter()
+            .map(|color| format!("{:>6}", color.to_string()))
+            .join(" | ")
+    );
+    for (position, probabilities) in Problem::color_probabilities(candidates).iter().enumerate() {
+        println!(
+            "{:>8} | {}",
+            position + 1,
+            probabilities
+                .iter()
+                .map(|probability| format!("{:>5.1}%", probability * 100.0))
+                .join(" | ")
+        );
+    }
+}
+
+/// Compares the bits each guess of `path` actually eliminated with the bits
+/// it was expected to, starting from `candidates` candidates.
+fn print_information_gain(path: &SolvePath<PASSWORD_LENGTH>, candidates: usize) {
+    println!("\nstep | expected bits | realized bits");
+
+    let mut before = candidates;
+    for (turn, step) in path.steps().iter().enumerate() {
+        let realized = f64::log2(before as f64) - f64::log2(step.remaining as f64);
+        println!(
+            "{:>4} | {:>13.3} | {:>13.3}",
+            turn + 1,
+            step.expected_gain,
+            realized
+        );
+        before = step.remaining;
+    }
+}
+
+/// Writes an `abbrev guesses` line per solved problem, the most guesses
+/// first and in index order among equal ones.
+fn write_difficulty_ranking(
+    path: &Path,
+    candidates: &[Problem],
+    tries: &[(usize, usize)],
+) -> io::Result<()> {
+    let mut ranking = BufWriter::new(File::create(path)?);
+    for (i, guesses) in tries
+        .iter()
+        .sorted_by_key(|(i, guesses)| (Reverse(guesses), i))
+    {
+        writeln!(ranking, "{} {guesses}", candidates[*i].abbrev())?;
+    }
+
+    ranking.flush()
+}
+
+/// Writes one `problem_index,step_number,entropy,hint,remaining` row per
+/// guess of every solve, for plotting how solves narrow down.
+fn write_detailed_csv(
+    path: &Path,
+    solved: &[(usize, SolvePath<PASSWORD_LENGTH>)],
+) -> io::Result<()> {
+    let mut csv = BufWriter::new(File::create(path)?);
+    writeln!(csv, "problem_index,step_number,entropy,hint,remaining")?;
+
+    for (i, solve) in solved {
+        for (step_number, step) in solve.steps().iter().enumerate() {
+            writeln!(
+                csv,
+                "{i},{},{},{},{}",
+                step_number + 1,
+                step.entropy,
+                step.hint,
+                step.remaining
+            )?;
         }
     }
 
-    if print_steps {
-        println!("=== {} ===", answer_set.last().unwrap());
-        println!("{} hits | {} remaining", 5, answer_set.len())
+    csv.flush()
+}
+
+/// Writes one `step,avg_entropy,avg_remaining,count` row per step number,
+/// averaged over the `count` solves that made that many guesses, so solves
+/// that finish early drop out of the later averages.
+fn write_entropy_decay(
+    path: &Path,
+    solved: &[(usize, SolvePath<PASSWORD_LENGTH>)],
+) -> io::Result<()> {
+    // (entropy, remaining, count) summed over the solves reaching each step
+    let mut sums: Vec<(f64, usize, usize)> = vec![];
+    for (_, solve) in solved {
+        if sums.len() < solve.steps().len() {
+            sums.resize(solve.steps().len(), (0.0, 0, 0));
+        }
+        for (sum, step) in sums.iter_mut().zip(solve.steps()) {
+            sum.0 += step.entropy;
+            sum.1 += step.remaining;
+            sum.2 += 1;
+        }
+    }
+
+    let mut csv = BufWriter::new(File::create(path)?);
+    writeln!(csv, "step,avg_entropy,avg_remaining,count")?;
+    for (step_number, (entropy, remaining, count)) in sums.into_iter().enumerate() {
+        writeln!(
+            csv,
+            "{},{},{},{count}",
+            step_number + 1,
+            entropy / count as f64,
+            remaining as f64 / count as f64
+        )?;
     }
 
-    return answers;
+    csv.flush()
 }
 
-fn assist_solving(problem_set: &[Problem]) {}
+/// Prints the entropy and expected remaining candidates of each opener,
+/// best entropy first.
+fn compare_openers(openers: &[Problem], candidates: &[Problem], feedback: FeedbackMode) {
+    let scores = PasswordSet::new(candidates.to_vec())
+        .entropy_all_parallel(openers, &Entropy, feedback)
+        .into_iter()
+        .map(|(opener, entropy)| {
+            let distribution = opener.hint_distribution(candidates, feedback);
+            (opener, entropy, expected_remaining(&distribution))
+        })
+        .collect_vec();
 
-fn solve_all(problem_set: &[Problem]) {
-    // do it for every possible case
-    let tries = problem_set
-        .clone()
+    let width = PASSWORD_LENGTH.max("opener".len());
+    println!("{:<width$} | entropy | expected remaining", "opener");
+    for (opener, entropy, expected) in scores {
+        println!(
+            "{:<width$} | {entropy:>7.3} | {expected:>18.3}",
+            opener.abbrev()
+        );
+    }
+}
+
+/// Guesses `strategy` needs for each of `candidates`, seeded per problem
+/// as in `solve_all`.
+fn guesses_per_secret<S: GuessStrategy<PASSWORD_LENGTH>>(
+    problem_set: &[Problem],
+    candidates: &[Problem],
+    strategy: &S,
+    options: SolveOptions,
+    solver: &SolverArgs,
+) -> Vec<usize> {
+    (0..candidates.len())
         .into_par_iter()
-        .enumerate()
-        .map(|(i, solution)| {
-            let attempts = solve_automatically(problem_set, solution.clone(), false).len();
+        .map(|i| {
+            let options = SolveOptions {
+                print_steps: false,
+                ..options.for_problem(i)
+            };
+            solve_automatically(
+                problem_set,
+                candidates,
+                &mut *secret_oracle(&candidates[i], &options, solver),
+                strategy,
+                options,
+                None,
+            )
+            .guesses()
+        })
+        .collect()
+}
 
-            println!("Solved problem #{i}");
-            (i, attempts)
+/// Prints every secret entropy and minimax solve in a different number of
+/// guesses, the biggest difference first.
+fn strategy_divergence(
+    problem_set: &[Problem],
+    candidates: &[Problem],
+    options: SolveOptions,
+    solver: &SolverArgs,
+) {
+    let entropy = guesses_per_secret(problem_set, candidates, &Entropy, options, solver);
+    let minimax = guesses_per_secret(problem_set, candidates, &Minimax, options, solver);
+
+    let divergent = (0..candidates.len())
+        .filter(|&i| entropy[i] != minimax[i])
+        .sorted_by_key(|&i| (Reverse(entropy[i].abs_diff(minimax[i])), i))
+        .collect_vec();
+
+    let width = PASSWORD_LENGTH.max("secret".len());
+    println!(
+        "{:<width$} | entropy | minimax | minimax - entropy",
+        "secret"
+    );
+    for &i in &divergent {
+        println!(
+            "{:<width$} | {:>7} | {:>7} | {:>+17}",
+            candidates[i].abbrev(),
+            entropy[i],
+            minimax[i],
+            minimax[i] as isize - entropy[i] as isize
+        );
+    }
+
+    let entropy_wins = divergent
+        .iter()
+        .filter(|&&i| entropy[i] < minimax[i])
+        .count();
+    println!(
+        "\n{} of {} secrets differ: entropy is faster on {entropy_wins}, minimax on {}",
+        divergent.len(),
+        candidates.len(),
+        divergent.len() - entropy_wins
+    );
+}
+
+/// Prints the average and max depth of `pinned`, a tree with a pinned
+/// opener, next to those of the tree opening with the strategy's choice.
+fn compare_with_strategy_opener<S: GuessStrategy<PASSWORD_LENGTH>>(
+    problem_set: &[Problem],
+    candidates: &[Problem],
+    pinned: &DecisionTree<PASSWORD_LENGTH>,
+    strategy: &S,
+    options: &SolveOptions,
+) {
+    let Some(chosen) = DecisionTree::build(problem_set, candidates, strategy, options) else {
+        return;
+    };
+
+    let opener = |tree: &DecisionTree<PASSWORD_LENGTH>| match tree {
+        DecisionTree::Leaf(solution) => solution.abbrev(),
+        DecisionTree::Node { guess, .. } => guess.abbrev(),
+    };
+    let width = PASSWORD_LENGTH.max("guess".len());
+    println!(
+        "\n{:<8} | {:<width$} | average | max depth",
+        "opener", "guess"
+    );
+    for (kind, tree) in [("pinned", pinned), ("strategy", &chosen)] {
+        println!(
+            "{kind:<8} | {:<width$} | {:>7.4} | {:>9}",
+            opener(tree),
+            tree.average_depth(),
+            tree.max_depth()
+        );
+    }
+}
+
+/// Worst case and average number of guesses `strategy` needs when it
+/// opens with `opener`, or `None` as soon as the worst case is known to be
+/// more than `bound`. A complete result lowers `bound` to its worst case.
+fn opener_outcome<S: GuessStrategy<PASSWORD_LENGTH>>(
+    problem_set: &[Problem],
+    candidates: &[Problem],
+    opener: &Problem,
+    strategy: &S,
+    options: &SolveOptions,
+    bound: &AtomicUsize,
+) -> Option<(usize, f64)> {
+    let (_, buckets) = opener.calculate_entropy(candidates, options.feedback);
+    // the largest buckets usually decide the worst case, so they prune soonest
+    let buckets = buckets
+        .into_iter()
+        .filter(|bucket| !bucket.is_empty())
+        .sorted_by_key(|bucket| Reverse(bucket.len()))
+        .collect_vec();
+
+    let mut worst_case = 0;
+    let mut total = 0;
+    for bucket in buckets {
+        let bucket = bucket.into_iter().cloned().collect_vec();
+        let tree = DecisionTree::build(problem_set, &bucket, strategy, options).unwrap();
+
+        worst_case = worst_case.max(1 + tree.max_depth());
+        if worst_case > bound.load(Ordering::Relaxed) {
+            return None;
+        }
+        total += tree.depths().iter().map(|depth| 1 + depth).sum::<usize>();
+    }
+
+    bound.fetch_min(worst_case, Ordering::Relaxed);
+    Some((worst_case, total as f64 / candidates.len() as f64))
+}
+
+/// Searches every opener in parallel for the lowest worst case of
+/// `strategy`, abandoning an opener once it is worse than the best found.
+/// Without `symmetric`, all of `problem_set` is tried; with it, only one
+/// opener per relabeling of the colors, which `candidates` must allow.
+fn best_openers<S: GuessStrategy<PASSWORD_LENGTH>>(
+    problem_set: &[Problem],
+    candidates: &[Problem],
+    strategy: &S,
+    options: &SolveOptions,
+    symmetric: bool,
+) {
+    let openers = if symmetric {
+        problem_set
+            .iter()
+            .map(Problem::canonical)
+            .unique()
+            .collect_vec()
+    } else {
+        problem_set.to_vec()
+    };
+    let bound = AtomicUsize::new(usize::MAX);
+    let done = AtomicUsize::new(0);
+
+    let outcomes = openers
+        .par_iter()
+        .filter_map(|opener| {
+            let outcome =
+                opener_outcome(problem_set, candidates, opener, strategy, options, &bound);
+            print_progress(done.fetch_add(1, Ordering::Relaxed) + 1, openers.len());
+            Some((opener, outcome?))
         })
         .collect::<Vec<_>>();
+    eprintln!();
 
-    let worst_case = tries.iter().max_by(|(_, t1), (_, t2)| t1.cmp(t2)).unwrap();
-
-    let average = tries.iter().map(|(_, b)| b).sum::<usize>() as f64 / tries.len() as f64;
+    let best = bound.load(Ordering::Relaxed);
+    let best_openers = outcomes
+        .iter()
+        .filter(|(_, (worst_case, _))| *worst_case == best)
+        .sorted_by(|(_, (_, average_a)), (_, (_, average_b))| average_a.total_cmp(average_b))
+        .collect_vec();
 
-    println!("Average: {average}");
     println!(
-        "Worst Case: {} | {} tries",
-        problem_set[worst_case.0], worst_case.1
+        "Searched {} openers, {} pruned",
+        openers.len(),
+        openers.len() - outcomes.len()
     );
+    if best_openers.is_empty() {
+        return;
+    }
+    println!("Best worst case: {best} tries\n");
+    println!("opener | average");
+    for (opener, (_, average)) in best_openers {
+        println!("{:<6} | {average:.4}", opener.abbrev());
+    }
 }
 
-fn initialize_problem_set() {
-    let mut problem_set = vec![
-        vec![Color::Red],
-        vec![Color::Green],
-        vec![Color::Blue],
-        vec![Color::Yellow],
-    ];
+/// Redraws a `[===   ] done/total` progress bar on stderr.
+fn print_progress(done: usize, total: usize) {
+    const WIDTH: usize = 30;
 
-    for _ in 0..PASSWORD_LENGTH - 1 {
-        problem_set = problem_set
-            .into_iter()
-            .cartesian_product(Color::all().into_iter())
-            .map(|(mut left, right)| {
-                left.push(right);
-                left
-            })
-            .collect_vec();
+    let filled = WIDTH * done / total.max(1);
+    eprint!("\r[{:<WIDTH$}] {done}/{total}", "=".repeat(filled));
+}
+
+/// Prints how well `opener` splits `candidates`, under the measure of every
+/// strategy.
+fn partition_stats(candidates: &[Problem], opener: &Problem, feedback: FeedbackMode) {
+    let distribution = opener.hint_distribution(candidates, feedback);
+
+    println!(
+        "Partition of {} candidates by {}:",
+        candidates.len(),
+        opener.abbrev()
+    );
+    println!(
+        "nonempty buckets:   {}",
+        distribution.iter().filter(|&&count| count > 0).count()
+    );
+    println!(
+        "largest bucket:     {}",
+        distribution.iter().max().unwrap_or(&0)
+    );
+    println!("entropy:            {:.3}", entropy(&distribution));
+    println!(
+        "expected remaining: {:.3}",
+        expected_remaining(&distribution)
+    );
+}
+
+/// Prints how many secrets give each hint against `opener`, by hinting every
+/// secret one at a time, and checks the counts against `hint_distribution`.
+fn first_guess_outcomes(candidates: &[Problem], opener: &Problem, feedback: FeedbackMode) {
+    let mut outcomes = vec![0; feedback.hints::<PASSWORD_LENGTH>()];
+    for secret in candidates {
+        outcomes[secret.hint(opener, feedback)] += 1;
     }
 
-    assert!(
-        PASSWORD_LENGTH == problem_set.iter().map(Vec::len).sum::<usize>() / problem_set.len(),
-        "average of length should equal length!"
+    assert_eq!(
+        outcomes,
+        opener.hint_distribution(candidates, feedback),
+        "hinting every secret disagrees with the hint distribution"
     );
 
-    let problem_set = problem_set
+    println!("Outcomes of opener {}:", opener.abbrev());
+    for (hint, count) in outcomes.iter().enumerate().filter(|(_, count)| **count > 0) {
+        println!(
+            "{:>20} | {count}",
+            feedback.decode::<PASSWORD_LENGTH>(hint).to_string()
+        );
+    }
+}
+
+/// Prints how many candidates each secret leaves after `opener`, and the
+/// `count` secrets leaving the most.
+fn most_ambiguous(candidates: &[Problem], opener: &Problem, feedback: FeedbackMode, count: usize) {
+    let distribution = opener.hint_distribution(candidates, feedback);
+    let remaining = candidates
         .iter()
-        .map(|m| Color::to_password(m))
+        .map(|secret| (secret, distribution[opener.hint(secret, feedback)]))
+        .sorted_by(|(secret_a, remaining_a), (secret_b, remaining_b)| {
+            remaining_b.cmp(remaining_a).then(secret_a.cmp(secret_b))
+        })
         .collect_vec();
 
+    println!("After opener {}:", opener.abbrev());
+    println!("remaining | secrets");
+    for group in remaining.chunk_by(|(_, remaining_a), (_, remaining_b)| remaining_a == remaining_b)
+    {
+        println!("{:>9} | {}", group[0].1, group.len());
+    }
+
+    println!("\nMost ambiguous secrets:");
+    for (secret, remaining) in remaining.iter().take(count) {
+        println!("{} | {remaining} remaining", secret.abbrev());
+    }
+}
+
+/// Prints how many positions of `guess` match `answer`, and the exact and
+/// misplaced counts with full feedback or the hint of any other mode.
+fn print_score(guess: &Problem, answer: &Problem, feedback: FeedbackMode) {
+    println!(
+        "{} against {}: {} in the right position",
+        guess.abbrev(),
+        answer.abbrev(),
+        guess.check_answer(answer)
+    );
+
+    match feedback {
+        FeedbackMode::Positional => {}
+        FeedbackMode::Full => {
+            let (exact, misplaced) = guess.check_answer_full(answer);
+            println!("exact: {exact}, misplaced: {misplaced}");
+        }
+        _ => println!("hint: {}", guess.feedback(answer, feedback)),
+    }
+}
+
+/// Prints the hint `guess` gets against each of `answers`, checking every
+/// one against the plain `check_answer` counts.
+fn dump_feedback_row(guess: &Problem, answers: &[Problem], feedback: FeedbackMode) {
+    println!("Hints of {}:", guess.abbrev());
+
+    for answer in answers {
+        let hint = answer.hint(guess, feedback);
+        let expected = match feedback {
+            FeedbackMode::Positional => answer.check_answer(guess),
+            FeedbackMode::Binary if answer == guess => PASSWORD_LENGTH,
+            FeedbackMode::Binary => 0,
+            FeedbackMode::Full => {
+                let (exact, misplaced) = answer.check_answer_full(guess);
+                Feedback::Full { exact, misplaced }.index::<PASSWORD_LENGTH>()
+            }
+            FeedbackMode::PerColor if answer == guess => feedback.solved::<PASSWORD_LENGTH>(),
+            FeedbackMode::PerColor => {
+                let hits = Color::all().map(|color| {
+                    (0..PASSWORD_LENGTH)
+                        .filter(|&i| guess[i] == color && answer[i] == color)
+                        .count()
+                });
+                assert_eq!(hits.iter().sum::<usize>(), answer.check_answer(guess));
+                Feedback::PerColor(Some(hits)).index::<PASSWORD_LENGTH>()
+            }
+        };
+        assert_eq!(
+            hint,
+            expected,
+            "hint of {} against {} disagrees with check_answer",
+            guess.abbrev(),
+            answer.abbrev()
+        );
+
+        println!(
+            "{} {}",
+            answer.abbrev(),
+            feedback.format_hint::<PASSWORD_LENGTH>(hint)
+        );
+    }
+}
+
+/// Prints the average of the optimal strategy over every password of
+/// length `N` next to the average of the entropy strategy.
+fn print_optimal<const N: usize>(options: &SolveOptions) {
+    let config = SolverConfig::<N, Entropy> {
+        feedback: options.feedback,
+        ..SolverConfig::default()
+    };
+    let problem_set = config.problem_set();
+
+    let optimal = optimal_total_guesses(&problem_set, &problem_set, options.feedback) as f64
+        / problem_set.len() as f64;
+    let greedy = DecisionTree::build(&problem_set, &problem_set, &Entropy, options)
+        .unwrap()
+        .average_depth();
+
+    println!("Length {N}: {} passwords", problem_set.len());
+    println!("Optimal average: {optimal}");
+    println!("Entropy average: {greedy}");
+    println!("Entropy is {:.2}% worse", (greedy / optimal - 1.0) * 100.0);
+}
+
+/// Prints the palette and an example of the password input the parser accepts.
+fn list_colors() {
+    println!("Colors:");
+    for color in Color::all() {
+        println!("  {} {color}", color.abbrev());
+    }
+
+    let example = Color::all()
+        .into_iter()
+        .cycle()
+        .take(PASSWORD_LENGTH)
+        .map(|color| color.abbrev())
+        .collect::<String>();
+    println!("\nExample password: {example}");
+}
+
+/// Writes every password of the problem set in abbrev form, followed by the count.
+fn dump_problem_set(
+    problem_set: &[Problem],
+    format: OutputFormat,
+    path: Option<&Path>,
+) -> io::Result<()> {
+    let mut out: Box<dyn Write> = match path {
+        Some(path) => Box::new(io::BufWriter::new(File::create(path)?)),
+        None => Box::new(io::stdout().lock()),
+    };
+
+    match format {
+        OutputFormat::Text => {
+            for password in problem_set {
+                writeln!(out, "{}", password.abbrev())?;
+            }
+            writeln!(out, "{} passwords", problem_set.len())?;
+        }
+        OutputFormat::Json => {
+            let passwords = problem_set
+                .iter()
+                .map(|password| format!("\"{}\"", password.abbrev()))
+                .join(",");
+            writeln!(
+                out,
+                "{{\"passwords\":[{passwords}],\"count\":{}}}",
+                problem_set.len()
+            )?;
+        }
+    }
+
+    out.flush()
+}
+
+/// Every password of `PASSWORD_LENGTH`, in code order.
+fn all_passwords() -> Vec<Password<PASSWORD_LENGTH>> {
+    (0..COLORS.pow(PASSWORD_LENGTH as u32))
+        .map(|code| Password::from_code(code).unwrap())
+        .collect_vec()
+}
+
+fn initialize_problem_set() {
+    let problem_set = all_passwords();
+
+    debug_assert_eq!(
+        problem_set.iter().collect::<HashSet<_>>().len(),
+        problem_set.len(),
+        "the problem set has duplicate passwords"
+    );
+
     let _ = PROBLEM_SET.set(problem_set);
 }
 
-#[derive(Parser, Debug)]
-struct CmdArgs {
+fn parse_seconds(seconds: &str) -> Result<Duration, String> {
+    let seconds = seconds.parse::<f64>().map_err(|err| err.to_string())?;
+    Duration::try_from_secs_f64(seconds).map_err(|err| err.to_string())
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, ValueEnum)]
+enum StrategyKind {
+    /// Maximize the entropy of the hint partition
+    #[default]
+    Entropy,
+    /// Minimize the largest number of candidates a hint can leave
+    Minimax,
+    /// Minimax, breaking ties by entropy and then in favor of candidates
+    MinimaxEntropy,
+    /// Minimize the expected number of candidates left
+    ExpectedRemaining,
+    /// Entropy on large candidate sets, minimax once --adaptive-threshold or fewer are left
+    Adaptive,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, ValueEnum)]
+enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// What build-tree prints.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, ValueEnum)]
+enum TreeFormat {
+    /// Average and maximum path length
+    #[default]
+    Summary,
+    Json,
+    /// Graphviz DOT, e.g. for `dot -Tsvg`
+    Dot,
+}
+
+// options shared by every command, accepted before or after its name
+#[derive(Args, Debug)]
+struct SolverArgs {
+    /// Pre-revealed secret positions, e.g. "r??g?"
+    #[arg(long, global = true)]
+    reveal: Option<Reveal<PASSWORD_LENGTH>>,
+    /// Score only this many random guesses (plus all remaining candidates) per step
+    #[arg(long, global = true, value_name = "K")]
+    sample_guesses: Option<usize>,
+    /// Score one guess per distinct partition once at most SIZE candidates are left
+    #[arg(
+        long,
+        global = true,
+        value_name = "SIZE",
+        conflicts_with = "prefer_diverse"
+    )]
+    dedupe_guesses: Option<usize>,
+    /// Give up and warn when a solve needs more than this many guesses
+    #[arg(long, global = true, value_name = "STEPS")]
+    max_steps: Option<usize>,
+    /// Stop a solve once it has run this many seconds, e.g. "0.5"
+    #[arg(long, global = true, value_name = "SECONDS", value_parser = parse_seconds)]
+    timeout: Option<Duration>,
+    /// Sample guesses instead when guesses times candidates exceeds this
+    #[arg(long, global = true, value_name = "SIZE")]
+    max_set_size: Option<usize>,
+    /// Seed for every random choice made by the solver
+    #[arg(long, global = true, default_value_t = 0)]
+    seed: u64,
+    /// How the next guess is chosen
+    #[arg(long, global = true, value_enum, default_value_t)]
+    strategy: StrategyKind,
+    /// Candidate count at which the adaptive strategy switches to minimax
+    #[arg(long, global = true, value_name = "SIZE", default_value_t = 16)]
+    adaptive_threshold: usize,
+    /// Which guess is made among equally scored ones
+    #[arg(long, global = true, value_enum, default_value_t)]
+    tie_break: TieBreak,
+    /// Break near-ties between guesses in favor of more distinct colors
+    #[arg(long, global = true)]
+    prefer_diverse: bool,
+    /// Feedback given by the game for each guess
+    #[arg(long, global = true, value_enum, default_value_t)]
+    feedback: FeedbackMode,
+    /// Reuse guess scores across solves meeting the same candidate set
+    #[arg(long, global = true)]
+    cache_scores: bool,
+    /// Keep candidates contradicting at most this many hints, as if hints could be lies
+    #[arg(long, global = true, value_name = "L", default_value_t = 0)]
+    lies: usize,
+    /// Have the secret lie about the first --lies guesses that aren't the secret
+    #[arg(long, global = true)]
+    tell_lies: bool,
+    /// Run everything on one thread, for readable traces while debugging.
+    /// Stats are the same as a parallel run's, unless --timeout cuts solves short
+    #[arg(long, global = true)]
+    no_parallel: bool,
+}
+
+#[derive(Args, Debug)]
+struct SolveAllArgs {
+    /// Record completed results to this file as they finish
+    #[arg(long, value_name = "PATH")]
+    checkpoint: Option<PathBuf>,
+    /// Skip the problems already recorded in --checkpoint
+    #[arg(long, requires = "checkpoint")]
+    resume: bool,
+    /// Write every step of every solve to this CSV file
+    #[arg(long, value_name = "PATH")]
+    detailed_csv: Option<PathBuf>,
+    /// Write the average entropy and candidates left at each step number to this CSV file
+    #[arg(long, value_name = "PATH")]
+    entropy_decay: Option<PathBuf>,
+    /// Solve grouped by the opener's hint instead of in index order
     #[arg(long)]
-    all: bool,
+    deterministic_all_order: bool,
+    /// Solve in a random order drawn from --seed, for unbiased timing
+    #[arg(long, conflicts_with = "deterministic_all_order")]
+    shuffle: bool,
+    /// Write the guess count histogram to this file
+    #[arg(long, value_name = "PATH")]
+    histogram: Option<PathBuf>,
+    /// Write every secret with its guess count to this file, hardest first
+    #[arg(long, value_name = "PATH")]
+    rank_difficulty: Option<PathBuf>,
+    /// Print every guess made for problem #INDEX and the candidates it leaves
+    #[arg(long, value_name = "INDEX")]
+    trace_index: Option<usize>,
+    /// Fail unless every solve ends on its own secret
+    #[arg(long, conflicts_with = "resume")]
+    verify_all: bool,
+}
+
+#[derive(Args, Debug)]
+struct TrialsArgs {
+    /// Number of random problems to solve
+    count: usize,
+    /// Solve this many batches of COUNT problems, each from its own seed
+    #[arg(long, value_name = "BATCHES", default_value = "1")]
+    repeat: NonZeroUsize,
+    /// Append one CSV row per problem to this file
+    #[arg(long, value_name = "PATH")]
+    append_results: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct SolveArgs {
+    /// Per-position color weights for the secret, e.g. "4,1,1,1;1,1,1,1;..."
+    #[arg(long, value_name = "WEIGHTS")]
+    position_weights: Option<ColorWeights<PASSWORD_LENGTH>>,
+    /// Print the expected and realized information gain of every guess
     #[arg(long)]
-    once: bool,
+    info_gain: bool,
+    /// Write the game to this file, to be checked later with replay
+    #[arg(long, value_name = "PATH")]
+    transcript: Option<PathBuf>,
+    /// Check that the solve ends on the actual solution, exiting with an error otherwise
+    #[arg(long, value_name = "BOOL", default_value_t = cfg!(debug_assertions), action = ArgAction::Set)]
+    verify: bool,
+}
+
+#[derive(Args, Debug)]
+struct OpenerArgs {
+    /// Opening guess to analyze, the strategy's choice if omitted
     #[arg(long)]
-    assist: bool,
+    opener: Option<Problem>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Solve every problem and report the average and worst case
+    SolveAll(SolveAllArgs),
+    /// Solve random problems drawn from --seed and report the average and worst case
+    Trials(TrialsArgs),
+    /// Solve one random problem drawn from --seed in detail
+    Solve(SolveArgs),
+    /// Recommend guesses for a game played elsewhere, reading "[guess] hint" lines
+    Assist {
+        /// Show how likely each color is at every position among the candidates left
+        #[arg(long)]
+        color_probabilities: bool,
+    },
+    /// Guess a random secret yourself, one password per line
+    Play {
+        /// Write the game to this file, to be checked later with replay
+        #[arg(long, value_name = "PATH")]
+        transcript: Option<PathBuf>,
+    },
+    /// Replay a game written with --transcript, checking every recorded hint
+    Replay { path: PathBuf },
+    /// Solve against an adversary giving whichever hint leaves the most candidates
+    Adversary,
+    /// Show every guess the strategy makes for this secret and what each one gained
+    Explain {
+        secret: Problem,
+        /// Show how likely each color is at every position after each guess
+        #[arg(long)]
+        color_probabilities: bool,
+    },
+    /// Build the strategy's full decision tree and report its path lengths
+    BuildTree {
+        #[arg(long, value_enum, default_value_t)]
+        format: TreeFormat,
+        /// Opening guess of the tree, the strategy's choice if omitted
+        #[arg(long)]
+        opener: Option<Problem>,
+    },
+    /// Compute the optimal average for short passwords and compare it with entropy
+    Optimal {
+        /// Password length, 1 to 3
+        #[arg(long, default_value_t = 3)]
+        length: usize,
+    },
+    /// Print the available colors and an example password
+    ListColors,
+    /// Print the hint a guess gets against every answer, or only the given ones
+    DumpFeedbackTable {
+        guess: Problem,
+        #[arg(long, value_name = "ANSWERS", value_delimiter = ',')]
+        answers: Vec<Problem>,
+    },
+    /// Print the hint GUESS gets against ANSWER, e.g. "rgbyr rrbgy"
+    Score { guess: Problem, answer: Problem },
+    /// Print the whole problem set, or write it to PATH
+    DumpProblemSet {
+        path: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Print the COUNT secrets leaving the most candidates after the opener
+    MostAmbiguous {
+        #[arg(default_value_t = 10)]
+        count: usize,
+        #[command(flatten)]
+        opener: OpenerArgs,
+    },
+    /// Search every opener for the lowest worst case of the strategy
+    BestOpener,
+    /// Print the bucket count, largest bucket, entropy and expected remaining of the opener
+    PartitionStats(OpenerArgs),
+    /// Print how many secrets give each hint against the opener
+    FirstGuessOutcomes(OpenerArgs),
+    /// Print the entropy and expected remaining of these openers, e.g. "rgbyr,rrrrr"
+    CompareOpeners {
+        #[arg(required = true, value_delimiter = ',')]
+        openers: Vec<Problem>,
+    },
+    /// Print the secrets entropy and minimax solve in different numbers of guesses
+    StrategyDivergence,
+    /// Print the summary of these merged solve-all --histogram files
+    MergeHistograms {
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// Solve secrets drawn from --position-weights with weighted entropy,
+    /// once assuming a uniform prior and once knowing the true one
+    PriorCost {
+        /// Number of secrets to draw
+        count: usize,
+        #[arg(long)]
+        position_weights: ColorWeights<PASSWORD_LENGTH>,
+    },
+}
+
+#[derive(Parser, Debug)]
+struct CmdArgs {
+    #[command(flatten)]
+    solver: SolverArgs,
+    #[command(subcommand)]
+    command: Command,
 }
 
 fn main() {
     let args = CmdArgs::parse();
 
+    if args.solver.no_parallel {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build_global()
+            .expect("the global thread pool is only built here");
+    }
+
     initialize_problem_set();
 
-    let problem_set = PROBLEM_SET.get().unwrap();
+    match args.solver.strategy {
+        StrategyKind::Entropy => run(&args, &Entropy),
+        StrategyKind::Minimax => run(&args, &Minimax),
+        StrategyKind::MinimaxEntropy => run(&args, &MinimaxEntropy),
+        StrategyKind::ExpectedRemaining => run(&args, &ExpectedRemaining),
+        StrategyKind::Adaptive => run(
+            &args,
+            &Adaptive {
+                threshold: args.solver.adaptive_threshold,
+            },
+        ),
+    }
+}
 
-    if args.all {
-        println!("Solving every combination of passwords");
-        solve_all(problem_set);
+fn exit_on_error<E: std::fmt::Display>(result: Result<(), E>) {
+    if let Err(err) = result {
+        eprintln!("error: {err}");
+        std::process::exit(1);
     }
+}
+
+fn run<S: GuessStrategy<PASSWORD_LENGTH> + Clone>(args: &CmdArgs, strategy: &S) {
+    let solver = &args.solver;
+    let problem_set = PROBLEM_SET.get().unwrap();
+    let candidates = match &solver.reveal {
+        Some(reveal) => problem_set
+            .iter()
+            .filter(|password| reveal.matches(password))
+            .cloned()
+            .collect_vec(),
+        None => problem_set.clone(),
+    };
+    let config = SolverConfig::<PASSWORD_LENGTH, _> {
+        palette: Color::all().to_vec(),
+        strategy: strategy.clone(),
+        feedback: solver.feedback,
+        seed: solver.seed,
+    };
+    let options = SolveOptions {
+        print_steps: true,
+        sample_guesses: solver.sample_guesses,
+        max_set_size: solver.max_set_size,
+        max_steps: solver.max_steps,
+        timeout: solver.timeout,
+        dedupe_guesses: solver.dedupe_guesses,
+        tie_break: solver.tie_break,
+        prefer_diverse: solver.prefer_diverse,
+        lies: solver.lies,
+        ..config.options()
+    };
+
+    let opener = |opener: &OpenerArgs| {
+        opener
+            .opener
+            .clone()
+            .unwrap_or_else(|| choose_guess(problem_set, &candidates, strategy, &options))
+    };
 
-    if args.once {
-        println!("Solving one problem in detail");
+    match &args.command {
+        Command::SolveAll(all) => {
+            println!("Solving every combination of passwords");
+            match solve_all(problem_set, &candidates, strategy, options, solver, all) {
+                Ok(report) => print_summary(&report),
+                Err(err) => exit_on_error(Err(err)),
+            }
+            if INTERRUPTED.load(Ordering::Relaxed) {
+                std::process::exit(INTERRUPTED_EXIT_CODE);
+            }
+        }
+        Command::Trials(trials) => {
+            match trials.repeat.get() {
+                1 => println!("Solving {} random problems", trials.count),
+                batches => println!(
+                    "Solving {batches} batches of {} random problems",
+                    trials.count
+                ),
+            }
+            exit_on_error(solve_trials(
+                problem_set,
+                &candidates,
+                strategy,
+                options,
+                solver,
+                trials,
+            ));
+        }
+        Command::Solve(solve) => {
+            println!("Solving one problem in detail");
+
+            // drawn from --seed, so that a solve can be run again
+            let mut rng = StdRng::seed_from_u64(options.seed);
+            let mut solution: Password<PASSWORD_LENGTH> = match &solve.position_weights {
+                Some(weights) => Password::generate_weighted_with(weights, &mut rng),
+                None => Password::generate_with(&mut rng),
+            };
+            if let Some(reveal) = &solver.reveal {
+                solution = reveal.apply(solution);
+            }
+            println!("solution: {}\n", solution);
+
+            let path = solve_automatically(
+                problem_set,
+                &candidates,
+                &mut *secret_oracle(&solution, &options, solver),
+                strategy,
+                options,
+                None,
+            );
+
+            if solve.info_gain {
+                print_information_gain(&path, candidates.len());
+            }
 
-        let solution: Password<PASSWORD_LENGTH> = Password::generate();
-        println!("solution: {}\n", solution);
+            if let Some(transcript_path) = &solve.transcript {
+                let transcript = Transcript::from_solve(solution.clone(), &path);
+                exit_on_error(std::fs::write(transcript_path, transcript.to_string()));
+            }
+
+            if solve.verify {
+                match path.solution() {
+                    Some(found) if solution.check_answer(found) == PASSWORD_LENGTH => {
+                        println!("\nverified: {found} is the solution");
+                    }
+                    found => {
+                        let found = found.map_or("nothing".to_string(), |found| found.to_string());
+                        eprintln!("error: solver ended on {found}, but the solution is {solution}");
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+        Command::Assist {
+            color_probabilities,
+        } => {
+            exit_on_error(assist_solving(
+                problem_set,
+                &candidates,
+                strategy,
+                options,
+                *color_probabilities,
+            ));
+        }
+        Command::Play { transcript } => {
+            exit_on_error(play(&candidates, options.feedback, transcript.as_deref()))
+        }
+        Command::Replay { path } => match replay(path) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(err) => exit_on_error(Err(err)),
+        },
+        Command::Adversary => {
+            println!("Solving against an adversary");
 
-        let _ = solve_automatically(problem_set, solution, true);
+            let mut adversary = Adversary::new(candidates.clone(), options.feedback);
+            let path = solve_automatically(
+                problem_set,
+                &candidates,
+                &mut adversary,
+                strategy,
+                options,
+                None,
+            );
+            println!("\nGuesses against the adversary: {}", path.guesses());
+        }
+        Command::Explain {
+            secret,
+            color_probabilities,
+        } => {
+            if !candidates.contains(secret) {
+                exit_on_error(Err(format!("{} is not a candidate", secret.abbrev())));
+            }
+            println!("Solving {}\n", secret.abbrev());
+
+            let path = solve_automatically(
+                problem_set,
+                &candidates,
+                &mut *secret_oracle(secret, &options, solver),
+                strategy,
+                options,
+                None,
+            );
+            print_information_gain(&path, candidates.len());
+
+            if *color_probabilities {
+                let mut remaining = candidates.clone();
+                for (turn, step) in path.steps().iter().enumerate() {
+                    remaining =
+                        filter_candidates(&remaining, &step.guess, step.hint, options.feedback);
+                    println!(
+                        "\nafter guess {}, {} candidates left",
+                        turn + 1,
+                        remaining.len()
+                    );
+                    print_color_probabilities(&remaining);
+                }
+            }
+        }
+        Command::BuildTree { format, opener } => {
+            if *format == TreeFormat::Summary {
+                println!("Building the decision tree");
+            }
+            let tree = match opener {
+                Some(opener) => DecisionTree::build_with_opener(
+                    problem_set,
+                    &candidates,
+                    opener,
+                    strategy,
+                    &options,
+                ),
+                None => DecisionTree::build(problem_set, &candidates, strategy, &options),
+            };
+            if let Some(tree) = tree {
+                match format {
+                    TreeFormat::Summary => {
+                        println!("Average: {}", tree.average_depth());
+                        println!("Max depth: {} tries", tree.max_depth());
+                        print_solved_curve(tree.max_depth(), |guesses| tree.solved_within(guesses));
+                        if opener.is_some() {
+                            compare_with_strategy_opener(
+                                problem_set,
+                                &candidates,
+                                &tree,
+                                strategy,
+                                &options,
+                            );
+                        }
+                    }
+                    TreeFormat::Json => println!("{}", tree.to_json()),
+                    TreeFormat::Dot => print!("{}", tree.to_dot(options.feedback)),
+                }
+            }
+        }
+        Command::Optimal { length } => match length {
+            1 => print_optimal::<1>(&options),
+            2 => print_optimal::<2>(&options),
+            3 => print_optimal::<3>(&options),
+            0 => exit_on_error(Err("length must be 1 to 3")),
+            _ => exit_on_error(Err(format!(
+                "length {length} is too large to search, at most 3 is supported"
+            ))),
+        },
+        Command::ListColors => list_colors(),
+        Command::DumpFeedbackTable { guess, answers } => {
+            let answers = if answers.is_empty() {
+                problem_set
+            } else {
+                answers
+            };
+            dump_feedback_row(guess, answers, options.feedback);
+        }
+        Command::Score { guess, answer } => print_score(guess, answer, options.feedback),
+        Command::DumpProblemSet { path, format } => {
+            exit_on_error(dump_problem_set(problem_set, *format, path.as_deref()));
+        }
+        Command::MostAmbiguous {
+            count,
+            opener: args,
+        } => {
+            most_ambiguous(&candidates, &opener(args), options.feedback, *count);
+        }
+        Command::BestOpener => {
+            let symmetric = solver.reveal.is_none();
+            best_openers(problem_set, &candidates, strategy, &options, symmetric);
+        }
+        Command::PartitionStats(args) => {
+            partition_stats(&candidates, &opener(args), options.feedback);
+        }
+        Command::FirstGuessOutcomes(args) => {
+            first_guess_outcomes(&candidates, &opener(args), options.feedback);
+        }
+        Command::CompareOpeners { openers } => {
+            compare_openers(openers, &candidates, options.feedback);
+        }
+        Command::StrategyDivergence => {
+            strategy_divergence(problem_set, &candidates, options, solver);
+        }
+        Command::MergeHistograms { paths } => match merge_histograms(paths) {
+            Ok(histogram) => {
+                print!("{histogram}");
+                print_summary(&SolveReport::new(histogram, &candidates));
+            }
+            Err(err) => exit_on_error(Err(err)),
+        },
+        Command::PriorCost {
+            count,
+            position_weights,
+        } => prior_cost(
+            problem_set,
+            &candidates,
+            options,
+            solver,
+            position_weights,
+            *count,
+        ),
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // WIP
-    // if args.assist {
-    //     assist_solving(problem_set);
-    // }
+    /// The `from_code` set must match the cartesian product it replaced,
+    /// order included, since problem indices refer to it.
+    #[test]
+    fn all_passwords_are_distinct_and_in_product_order() {
+        let problem_set = all_passwords();
+        let product = (0..PASSWORD_LENGTH)
+            .map(|_| Color::all())
+            .multi_cartesian_product()
+            .map(|colors| Password::new(&colors))
+            .collect_vec();
+
+        assert_eq!(problem_set.len(), 1024);
+        assert_eq!(problem_set.iter().collect::<HashSet<_>>().len(), 1024);
+        assert_eq!(problem_set, product);
+    }
 }
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use crate::{partition_representatives, FeedbackMode, Password};
+
+/// Fewest guesses any strategy guessing from `problem_set` needs in total
+/// over every secret of `candidates`, counted like `solve_automatically`
+/// counts them. Dividing by the number of candidates gives the optimal
+/// average.
+///
+/// Searches every candidate subset a game can reach, so this is only
+/// feasible for small problem sets.
+pub fn optimal_total_guesses<const N: usize>(
+    problem_set: &[Password<N>],
+    candidates: &[Password<N>],
+    feedback: FeedbackMode,
+) -> usize {
+    let mut candidates = candidates.to_vec();
+    candidates.sort_unstable();
+
+    total_guesses(problem_set, candidates, feedback, &mut HashMap::new())
+}
+
+/// `optimal_total_guesses` of sorted `candidates`, remembering the total of
+/// every set in `memo`.
+fn total_guesses<const N: usize>(
+    problem_set: &[Password<N>],
+    candidates: Vec<Password<N>>,
+    feedback: FeedbackMode,
+    memo: &mut HashMap<Vec<Password<N>>, usize>,
+) -> usize {
+    if candidates.len() <= 1 {
+        return 0;
+    }
+    if let Some(&total) = memo.get(&candidates) {
+        return total;
+    }
+
+    let mut best = usize::MAX;
+    for guess in partition_representatives(problem_set, &candidates, feedback) {
+        let (_, buckets) = guess.calculate_entropy(&candidates, feedback);
+        let mut buckets = buckets
+            .into_iter()
+            .filter(|bucket| bucket.len() > 1)
+            .map(|bucket| bucket.into_iter().cloned().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        // a guess that doesn't split the set only delays the others
+        if buckets
+            .iter()
+            .any(|bucket| bucket.len() == candidates.len())
+        {
+            continue;
+        }
+
+        // every secret takes this guess, and those sharing a bucket at least
+        // one more
+        let mut bound = candidates.len() + buckets.iter().map(Vec::len).sum::<usize>();
+        if bound >= best {
+            continue;
+        }
+
+        buckets.sort_by_key(|bucket| std::cmp::Reverse(bucket.len()));
+        for bucket in buckets {
+            let size = bucket.len();
+            bound += total_guesses(problem_set, bucket, feedback, memo) - size;
+            if bound >= best {
+                break;
+            }
+        }
+        best = best.min(bound);
+    }
+
+    memo.insert(candidates, best);
+    best
+}
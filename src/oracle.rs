@@ -0,0 +1,170 @@
+use std::collections::VecDeque;
+
+use itertools::Itertools;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{filter_candidates, FeedbackMode, Password};
+
+/// Where the hints for the solver's guesses come from.
+pub trait Oracle<const N: usize> {
+    /// The hint for `guess`, or `None` once no more hints can be given.
+    fn feedback(&mut self, guess: &Password<N>) -> Option<usize>;
+}
+
+/// Hints computed from a secret the oracle knows.
+#[derive(Clone, Debug)]
+pub struct KnownSecret<const N: usize> {
+    pub secret: Password<N>,
+    pub feedback: FeedbackMode,
+}
+
+impl<const N: usize> Oracle<N> for KnownSecret<N> {
+    fn feedback(&mut self, guess: &Password<N>) -> Option<usize> {
+        Some(self.secret.hint(guess, self.feedback))
+    }
+}
+
+/// Hints computed from a secret, lying about the first `lies` guesses that
+/// aren't the secret. A lie is a random hint other than the true one that
+/// some password would give, and never the solved hint.
+#[derive(Clone, Debug)]
+pub struct Liar<const N: usize> {
+    secret: Password<N>,
+    feedback: FeedbackMode,
+    lies: usize,
+    rng: StdRng,
+}
+
+impl<const N: usize> Liar<N> {
+    pub fn new(secret: Password<N>, feedback: FeedbackMode, lies: usize, seed: u64) -> Self {
+        Self {
+            secret,
+            feedback,
+            lies,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Number of lies not told yet.
+    pub fn lies_left(&self) -> usize {
+        self.lies
+    }
+}
+
+impl<const N: usize> Oracle<N> for Liar<N> {
+    fn feedback(&mut self, guess: &Password<N>) -> Option<usize> {
+        let truth = self.secret.hint(guess, self.feedback);
+        if self.lies == 0 || *guess == self.secret {
+            return Some(truth);
+        }
+
+        let lies = (0..)
+            .map_while(Password::from_code)
+            .map(|password| guess.hint(&password, self.feedback))
+            .filter(|&hint| hint != truth && hint != self.feedback.solved::<N>())
+            .unique()
+            .collect::<Vec<_>>();
+
+        match lies.choose(&mut self.rng) {
+            Some(&lie) => {
+                self.lies -= 1;
+                Some(lie)
+            }
+            None => Some(truth),
+        }
+    }
+}
+
+/// Hints from an adversary that commits to no secret, answering every
+/// guess with the hint leaving the most candidates. This greedy choice
+/// usually makes the solver need as many guesses as its worst secret, but
+/// not always: a smaller bucket can occasionally be harder to finish.
+#[derive(Clone, Debug)]
+pub struct Adversary<const N: usize> {
+    candidates: Vec<Password<N>>,
+    feedback: FeedbackMode,
+}
+
+impl<const N: usize> Adversary<N> {
+    /// An adversary whose secret can be any of `candidates`, which should be
+    /// the candidates the solver starts from.
+    pub fn new(candidates: Vec<Password<N>>, feedback: FeedbackMode) -> Self {
+        Self {
+            candidates,
+            feedback,
+        }
+    }
+
+    /// The secrets still consistent with every hint given.
+    pub fn candidates(&self) -> &[Password<N>] {
+        &self.candidates
+    }
+}
+
+impl<const N: usize> Oracle<N> for Adversary<N> {
+    fn feedback(&mut self, guess: &Password<N>) -> Option<usize> {
+        let distribution = guess.hint_distribution(&self.candidates, self.feedback);
+        // among equally large buckets, avoid admitting the guess was right
+        let solved = self.feedback.solved::<N>();
+        let (hint, _) = distribution
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .max_by_key(|(hint, count)| (**count, *hint != solved))?;
+
+        self.candidates = filter_candidates(&self.candidates, guess, hint, self.feedback);
+        Some(hint)
+    }
+}
+
+/// Hints replayed from a list, e.g. one recorded from a real game.
+#[derive(Clone, Debug, Default)]
+pub struct Scripted {
+    hints: VecDeque<usize>,
+}
+
+impl Scripted {
+    pub fn new(hints: impl IntoIterator<Item = usize>) -> Self {
+        Self {
+            hints: hints.into_iter().collect(),
+        }
+    }
+}
+
+impl<const N: usize> Oracle<N> for Scripted {
+    fn feedback(&mut self, _guess: &Password<N>) -> Option<usize> {
+        self.hints.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{solve_automatically, Entropy, SolverConfig};
+
+    #[test]
+    fn scripted_hints_replay_a_solve() {
+        let config = SolverConfig::<3, Entropy>::default();
+        let problem_set = config.problem_set();
+        let options = config.options();
+        let solve = |oracle: &mut dyn Oracle<3>| {
+            solve_automatically(&problem_set, &problem_set, oracle, &Entropy, options, None)
+        };
+
+        let secret = "gyb".parse::<Password<3>>().unwrap();
+        let known = solve(&mut KnownSecret {
+            secret: secret.clone(),
+            feedback: options.feedback,
+        });
+        let hints = known.steps().iter().map(|step| step.hint).collect_vec();
+
+        let scripted = solve(&mut Scripted::new(hints.clone()));
+        assert_eq!(scripted.to_string(), known.to_string());
+        assert_eq!(scripted.solution(), Some(&secret));
+
+        // out of hints, the solve stops without a solution
+        let cut = solve(&mut Scripted::new(hints[..1].to_vec()));
+        assert_eq!(cut.guesses(), 1);
+        assert_eq!(cut.solution(), None);
+    }
+}
@@ -0,0 +1,728 @@
+use std::str::FromStr;
+
+use derive_more::Display;
+use itertools::Itertools;
+use rand::{thread_rng, Fill, Rng};
+
+use crate::{entropy, Color, ColorWeights, Feedback, FeedbackMode, COLORS};
+
+impl<const N: usize> Fill for Password<N> {
+    fn try_fill<R: rand::Rng + ?Sized>(&mut self, rng: &mut R) -> Result<(), rand::Error> {
+        self.fill_weighted(rng, &ColorWeights::uniform());
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Display)]
+pub enum PasswordError {
+    #[display(fmt = "expected {} positions, found {}", expected, found)]
+    WrongLength { expected: usize, found: usize },
+    #[display(fmt = "unknown color '{}'", _0)]
+    UnknownColor(char),
+    #[display(fmt = "invalid color weights: {}", _0)]
+    InvalidWeights(String),
+    #[display(fmt = "{} is not in the palette", _0)]
+    OutsidePalette(Color),
+}
+
+impl std::error::Error for PasswordError {}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Password<const N: usize> {
+    answer: [Color; N],
+}
+
+impl<const N: usize> std::fmt::Display for Password<{ N }> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.answer)
+    }
+}
+
+impl<const N: usize> TryFrom<&[Color]> for Password<N> {
+    type Error = PasswordError;
+
+    fn try_from(comb: &[Color]) -> Result<Self, Self::Error> {
+        let () = Self::NOT_EMPTY;
+        let answer = comb.try_into().map_err(|_| PasswordError::WrongLength {
+            expected: N,
+            found: comb.len(),
+        })?;
+
+        Ok(Self { answer })
+    }
+}
+
+impl<const N: usize> TryFrom<Vec<Color>> for Password<N> {
+    type Error = PasswordError;
+
+    fn try_from(comb: Vec<Color>) -> Result<Self, Self::Error> {
+        Self::try_from(comb.as_slice())
+    }
+}
+
+/// The all-red password.
+impl<const N: usize> Default for Password<N> {
+    fn default() -> Self {
+        let () = Self::NOT_EMPTY;
+
+        Self {
+            answer: [Color::Red; N],
+        }
+    }
+}
+
+/// Builds a password position by position, e.g.
+/// `Password::builder().set(1, Color::Green).build()`.
+#[derive(Clone, Debug)]
+pub struct PasswordBuilder<const N: usize> {
+    password: Password<N>,
+}
+
+impl<const N: usize> PasswordBuilder<N> {
+    /// Colors `position`, panicking if it is `N` or more like indexing does.
+    pub fn set(mut self, position: usize, color: Color) -> Self {
+        self.password.answer[position] = color;
+        self
+    }
+
+    pub fn build(self) -> Password<N> {
+        self.password
+    }
+}
+
+/// The color at a position, panicking if it is `N` or more like slice
+/// indexing does.
+impl<const N: usize> std::ops::Index<usize> for Password<N> {
+    type Output = Color;
+
+    fn index(&self, position: usize) -> &Color {
+        &self.answer[position]
+    }
+}
+
+/// Parses the abbrev form, e.g. `rgbyr`.
+impl<const N: usize> FromStr for Password<N> {
+    type Err = PasswordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .chars()
+            .map(|abbrev| Color::from_abbrev(abbrev).ok_or(PasswordError::UnknownColor(abbrev)))
+            .collect::<Result<Vec<_>, _>>()?
+            .try_into()
+    }
+}
+
+impl<const N: usize> Password<{ N }> {
+    /// Checked by every constructor, so that a `Password<0>` fails to
+    /// compile: without positions there is nothing to hint at.
+    ///
+    /// ```compile_fail
+    /// let _ = oneshot_password::Password::<0>::default();
+    /// ```
+    const NOT_EMPTY: () = assert!(N > 0, "a password needs at least one position");
+
+    pub fn generate() -> Self {
+        Self::generate_with(&mut thread_rng())
+    }
+
+    /// `generate`, drawing the colors from `rng`.
+    pub fn generate_with<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut password = Self::default();
+        rng.fill(&mut password);
+
+        password
+    }
+
+    /// Generates a password whose colors follow `weights` position by position.
+    pub fn generate_weighted(weights: &ColorWeights<N>) -> Self {
+        Self::generate_weighted_with(weights, &mut thread_rng())
+    }
+
+    /// `generate_weighted`, drawing the colors from `rng`.
+    pub fn generate_weighted_with<R: Rng + ?Sized>(weights: &ColorWeights<N>, rng: &mut R) -> Self {
+        let mut password = Self::default();
+        password.fill_weighted(rng, weights);
+
+        password
+    }
+
+    fn fill_weighted<R: Rng + ?Sized>(&mut self, rng: &mut R, weights: &ColorWeights<N>) {
+        for (position, color) in self.answer.iter_mut().enumerate() {
+            *color = weights.sample(position, rng);
+        }
+    }
+
+    /// Starts from the all-red password, see `PasswordBuilder`.
+    pub fn builder() -> PasswordBuilder<N> {
+        PasswordBuilder {
+            password: Self::default(),
+        }
+    }
+
+    /// Panics if `comb` doesn't hold exactly `N` colors, see `TryFrom` for
+    /// the checked version.
+    pub fn new(comb: &[Color]) -> Password<N> {
+        Self::try_from(comb).unwrap_or_else(|err| panic!("invalid password: {err}"))
+    }
+
+    /// The password spelled with color abbreviations, e.g. `rgbyr`.
+    pub fn abbrev(&self) -> String {
+        self.answer.iter().map(Color::abbrev).collect()
+    }
+
+    /// Relabels every color: the color with index `i + 1` becomes the color
+    /// with index `perm[i] + 1`.
+    ///
+    /// Applying the same permutation to a guess and a secret keeps their hint
+    /// unchanged. Panics if `perm` isn't a permutation of `0..COLORS`.
+    pub fn apply_permutation(&self, perm: [usize; COLORS]) -> Self {
+        let mut seen = [false; COLORS];
+        for &target in &perm {
+            assert!(
+                target < COLORS && !std::mem::replace(&mut seen[target], true),
+                "{perm:?} is not a permutation of 0..{COLORS}"
+            );
+        }
+
+        let mut answer = self.answer;
+        for color in answer.iter_mut() {
+            *color = Color::from_index(perm[color.index() - 1] + 1).unwrap();
+        }
+
+        Self { answer }
+    }
+
+    /// The smallest password this one maps to under any color permutation,
+    /// relabeling colors in order of first appearance. Two passwords are
+    /// equivalent up to relabeling exactly when their canonical forms match.
+    pub fn canonical(&self) -> Self {
+        let mut perm = [usize::MAX; COLORS];
+        let mut next = 0;
+        for color in &self.answer {
+            let slot = &mut perm[color.index() - 1];
+            if *slot == usize::MAX {
+                *slot = next;
+                next += 1;
+            }
+        }
+
+        // colors that don't appear take the remaining labels
+        for slot in perm.iter_mut().filter(|slot| **slot == usize::MAX) {
+            *slot = next;
+            next += 1;
+        }
+
+        self.apply_permutation(perm)
+    }
+
+    /// The first color of this password that `palette` doesn't contain.
+    pub fn color_outside(&self, palette: &[Color]) -> Option<Color> {
+        self.answer
+            .iter()
+            .find(|color| !palette.contains(color))
+            .copied()
+    }
+
+    /// Whether every color of this password is among the first `colors` of
+    /// the palette, i.e. has an index in `1..=colors`. Every password is
+    /// valid for `COLORS`.
+    pub fn is_valid_for(&self, colors: usize) -> bool {
+        self.answer.iter().all(|color| color.index() <= colors)
+    }
+
+    /// This password with the colors at positions `a` and `b` exchanged.
+    /// Panics if either is not below N.
+    pub fn swap_positions(&self, a: usize, b: usize) -> Self {
+        let mut answer = self.answer;
+        answer.swap(a, b);
+
+        Self { answer }
+    }
+
+    /// Every password differing from this one in exactly one position:
+    /// `N * (COLORS - 1)` of them.
+    pub fn neighbors_one_change(&self) -> impl Iterator<Item = Password<N>> + '_ {
+        (0..N).flat_map(move |position| {
+            Color::all()
+                .into_iter()
+                .filter(move |color| *color != self.answer[position])
+                .map(move |color| {
+                    let mut answer = self.answer;
+                    answer[position] = color;
+                    Self { answer }
+                })
+        })
+    }
+
+    /// Every distinct password made by swapping two positions of this one.
+    /// Swaps of two equal colors are skipped, as they'd give this password.
+    pub fn neighbors_swap(&self) -> impl Iterator<Item = Password<N>> + '_ {
+        (0..N)
+            .tuple_combinations()
+            .filter(|&(a, b)| self.answer[a] != self.answer[b])
+            .map(|(a, b)| self.swap_positions(a, b))
+    }
+
+    /// The password whose colors are the base-`COLORS` digits of `code`,
+    /// most significant first, so codes count up in the order of
+    /// `Color::all()`. `None` if `code` is `COLORS^N` or more.
+    pub fn from_code(mut code: usize) -> Option<Self> {
+        let mut password = Self::default();
+        for color in password.answer.iter_mut().rev() {
+            *color = Color::from_index(code % COLORS + 1).unwrap();
+            code /= COLORS;
+        }
+
+        (code == 0).then_some(password)
+    }
+
+    /// The inverse of `from_code`.
+    pub fn to_code(&self) -> usize {
+        self.answer
+            .iter()
+            .fold(0, |code, color| code * COLORS + color.index() - 1)
+    }
+
+    /// Number of distinct colors left at every position among `candidates`:
+    /// 1 where they all agree, so only the other positions are uncertain.
+    pub fn most_informative_positions(candidates: &[Password<N>]) -> [usize; N] {
+        let mut seen = [[false; COLORS]; N];
+        for candidate in candidates {
+            for (seen, color) in seen.iter_mut().zip(&candidate.answer) {
+                seen[color.index() - 1] = true;
+            }
+        }
+
+        seen.map(|seen| seen.iter().filter(|&&seen| seen).count())
+    }
+
+    /// Number of positions of this password with `color`.
+    pub fn count_color(&self, color: Color) -> usize {
+        self.answer.iter().filter(|&&slot| slot == color).count()
+    }
+
+    /// Fraction of `candidates` with each color at every position, indexed
+    /// by position and then by `Color::index() - 1`. All zero if there are
+    /// no candidates.
+    pub fn color_probabilities(candidates: &[Password<N>]) -> [[f64; COLORS]; N] {
+        let mut counts = [[0; COLORS]; N];
+        for candidate in candidates {
+            for (position, counts) in counts.iter_mut().enumerate() {
+                counts[candidate[position].index() - 1] += 1;
+            }
+        }
+
+        counts.map(|counts| counts.map(|count| count as f64 / candidates.len().max(1) as f64))
+    }
+
+    /// Number of different colors used by this password.
+    pub fn distinct_colors(&self) -> usize {
+        Color::all()
+            .into_iter()
+            .filter(|&color| self.count_color(color) > 0)
+            .count()
+    }
+
+    pub fn check_answer(&self, answer: &Password<N>) -> usize {
+        let mut correct = 0;
+
+        for i in 0..N {
+            if self.answer[i] == answer.answer[i] {
+                correct += 1;
+            }
+        }
+
+        correct
+    }
+
+    /// Correct positions and correct colors in the wrong position, counting
+    /// each color of `answer` at most once.
+    pub fn check_answer_full(&self, answer: &Password<N>) -> (usize, usize) {
+        let exact = self.check_answer(answer);
+
+        let common = Color::all()
+            .into_iter()
+            .map(|color| self.count_color(color).min(answer.count_color(color)))
+            .sum::<usize>();
+
+        (exact, common - exact)
+    }
+
+    /// The feedback the game gives for this guess against `answer`.
+    pub fn feedback(&self, answer: &Password<N>, feedback: FeedbackMode) -> Feedback {
+        match feedback {
+            FeedbackMode::Positional => Feedback::Positional(self.check_answer(answer)),
+            FeedbackMode::Full => {
+                let (exact, misplaced) = self.check_answer_full(answer);
+                Feedback::Full { exact, misplaced }
+            }
+            FeedbackMode::Binary => Feedback::Binary(self.check_answer(answer) == N),
+            FeedbackMode::PerColor => {
+                let mut correct = [0; COLORS];
+                for (guess, ans) in self.answer.iter().zip(&answer.answer) {
+                    if guess == ans {
+                        correct[guess.index() - 1] += 1;
+                    }
+                }
+                debug_assert_eq!(correct.iter().sum::<usize>(), self.check_answer(answer));
+
+                Feedback::PerColor((correct.iter().sum::<usize>() < N).then_some(correct))
+            }
+        }
+    }
+
+    /// The hint given for this guess against `answer` under `feedback`, as
+    /// an index into its partition.
+    pub fn hint(&self, answer: &Password<N>, feedback: FeedbackMode) -> usize {
+        self.feedback(answer, feedback).index::<N>()
+    }
+
+    /// Whether this password could be the secret, given that `description`
+    /// was guessed and got `hint`.
+    pub fn matches_description(
+        &self,
+        description: &Password<N>,
+        hint: usize,
+        feedback: FeedbackMode,
+    ) -> bool {
+        description.hint(self, feedback) == hint
+    }
+
+    /// Counts how many answers give each hint against this guess, indexed by
+    /// the hint.
+    pub fn hint_distribution(
+        &self,
+        answer_set: &[Password<N>],
+        feedback: FeedbackMode,
+    ) -> Vec<usize> {
+        self.hint_distribution_of(answer_set, feedback)
+    }
+
+    /// `hint_distribution` over answers from any iterator, e.g. a lazily
+    /// filtered set that is never collected.
+    pub fn hint_distribution_of<'a>(
+        &self,
+        answers: impl IntoIterator<Item = &'a Password<N>>,
+        feedback: FeedbackMode,
+    ) -> Vec<usize> {
+        let mut distribution = vec![0; feedback.hints::<N>()];
+
+        for ans in answers {
+            distribution[self.hint(ans, feedback)] += 1;
+        }
+
+        distribution
+    }
+
+    /// The `entropy` of this guess against `answers`, counting hints without
+    /// building the buckets of `calculate_entropy`.
+    pub fn entropy_only<'a>(
+        &self,
+        answers: impl IntoIterator<Item = &'a Password<N>>,
+        feedback: FeedbackMode,
+    ) -> f64 {
+        entropy(&self.hint_distribution_of(answers, feedback))
+    }
+
+    /// How many passwords of `set` are at each Hamming distance from this
+    /// one, indexed by the distance. This is the positional hint
+    /// distribution read backwards, `profile[d] == distribution[N - d]`.
+    pub fn distance_profile(&self, set: &[Password<N>]) -> Vec<usize> {
+        let mut profile = vec![];
+        self.distance_profile_into(set, &mut profile);
+
+        profile
+    }
+
+    /// `distance_profile` written into `profile`, reusing its allocation.
+    pub fn distance_profile_into(&self, set: &[Password<N>], profile: &mut Vec<usize>) {
+        profile.clear();
+        profile.resize(N + 1, 0);
+
+        for password in set {
+            profile[N - self.check_answer(password)] += 1;
+        }
+    }
+
+    /// Partitions `answer_set` into buckets indexed by the hint each answer
+    /// gives against this guess. The entropy of an empty `answer_set` is 0.0.
+    pub fn calculate_entropy<'a>(
+        &self,
+        answer_set: &'a [Password<N>],
+        feedback: FeedbackMode,
+    ) -> (f64, Vec<Vec<&'a Password<N>>>) {
+        let mut buckets = vec![vec![]; feedback.hints::<N>()];
+
+        for ans in answer_set {
+            buckets[self.hint(ans, feedback)].push(ans);
+        }
+
+        let entropy = entropy(&buckets.iter().map(Vec::len).collect_vec());
+
+        (entropy, buckets)
+    }
+}
+
+/// A secret with some positions already revealed, e.g. `r??g?`.
+///
+/// Revealed positions are always correct in the secret, so only candidates
+/// agreeing with them are kept. Every guess then scores the revealed
+/// positions identically for all candidates, which is exactly the same as
+/// counting them as always-correct.
+#[derive(Clone, Debug)]
+pub struct Reveal<const N: usize> {
+    slots: [Option<Color>; N],
+}
+
+impl<const N: usize> FromStr for Reveal<N> {
+    type Err = PasswordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars = s.trim().chars().collect_vec();
+        if chars.len() != N {
+            return Err(PasswordError::WrongLength {
+                expected: N,
+                found: chars.len(),
+            });
+        }
+
+        let mut slots = [None; N];
+        for (slot, abbrev) in slots.iter_mut().zip(chars) {
+            *slot = match abbrev {
+                '?' => None,
+                _ => Some(Color::from_abbrev(abbrev).ok_or(PasswordError::UnknownColor(abbrev))?),
+            };
+        }
+
+        Ok(Self { slots })
+    }
+}
+
+impl<const N: usize> Reveal<{ N }> {
+    pub fn matches(&self, password: &Password<N>) -> bool {
+        self.slots
+            .iter()
+            .zip(password.answer.iter())
+            .all(|(slot, color)| slot.is_none_or(|revealed| revealed == *color))
+    }
+
+    /// Overwrites the revealed positions of `password`.
+    pub fn apply(&self, mut password: Password<N>) -> Password<N> {
+        for (color, slot) in password.answer.iter_mut().zip(self.slots.iter()) {
+            if let Some(revealed) = slot {
+                *color = *revealed;
+            }
+        }
+
+        password
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::ValueEnum;
+
+    use super::*;
+
+    /// Every password of length `N`, ordered by `to_code`.
+    fn all<const N: usize>() -> Vec<Password<N>> {
+        (0..COLORS.pow(N as u32))
+            .map(|code| Password::from_code(code).unwrap())
+            .collect()
+    }
+
+    fn permutations() -> Vec<[usize; COLORS]> {
+        (0..COLORS)
+            .permutations(COLORS)
+            .map(|perm| perm.try_into().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn permuting_guess_and_secret_keeps_the_hint() {
+        let passwords = all::<3>();
+
+        for perm in permutations() {
+            for guess in &passwords {
+                for secret in &passwords {
+                    let (guess_p, secret_p) = (
+                        guess.apply_permutation(perm),
+                        secret.apply_permutation(perm),
+                    );
+
+                    assert_eq!(guess_p.check_answer(&secret_p), guess.check_answer(secret));
+                    assert_eq!(
+                        guess_p.check_answer_full(&secret_p),
+                        guess.check_answer_full(secret)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn canonical_is_shared_by_relabelings_only() {
+        let parse = |abbrev: &str| abbrev.parse::<Password<4>>().unwrap();
+        assert_eq!(parse("yybg").canonical(), parse("rrgb"));
+        assert_eq!(parse("gyyg").canonical(), parse("rggr"));
+        assert_ne!(parse("rrgb").canonical(), parse("rgbb").canonical());
+
+        for password in all::<4>() {
+            let canonical = password.canonical();
+            assert_eq!(canonical.canonical(), canonical);
+
+            for perm in permutations() {
+                assert_eq!(password.apply_permutation(perm).canonical(), canonical);
+            }
+        }
+
+        let passwords = all::<4>();
+        for (a, b) in passwords.iter().tuple_combinations() {
+            if a.canonical() == b.canonical() {
+                assert!(permutations()
+                    .into_iter()
+                    .any(|perm| a.apply_permutation(perm) == *b));
+            }
+        }
+    }
+
+    #[test]
+    fn one_change_neighbors_differ_in_one_position() {
+        for password in all::<4>() {
+            let neighbors = password.neighbors_one_change().collect_vec();
+
+            assert_eq!(neighbors.len(), 4 * (COLORS - 1));
+            assert!(neighbors.iter().all_unique());
+            for neighbor in &neighbors {
+                assert_eq!(neighbor.check_answer(&password), 3);
+            }
+        }
+    }
+
+    #[test]
+    fn swap_neighbors_skip_equal_colors() {
+        let parse = |abbrev: &str| abbrev.parse::<Password<5>>().unwrap();
+
+        // of the 10 swaps, only the one of the two reds is skipped
+        let neighbors = parse("rrgby").neighbors_swap().collect_vec();
+        assert_eq!(neighbors.len(), 10 - 1);
+        assert!(neighbors.iter().all_unique());
+        assert!(neighbors.contains(&parse("rrbgy")));
+
+        assert_eq!(parse("rrrrr").neighbors_swap().count(), 0);
+    }
+
+    #[test]
+    fn filter_candidates_keeps_what_matches_the_description() {
+        let passwords = all::<3>();
+
+        for &feedback in FeedbackMode::value_variants() {
+            for guess in &passwords {
+                let distribution = guess.hint_distribution(&passwords, feedback);
+                let mut kept = 0;
+
+                for (hint, &count) in distribution.iter().enumerate() {
+                    let filtered = crate::filter_candidates(&passwords, guess, hint, feedback);
+                    let matching = passwords
+                        .iter()
+                        .filter(|candidate| candidate.matches_description(guess, hint, feedback))
+                        .cloned()
+                        .collect_vec();
+
+                    assert_eq!(filtered, matching);
+                    assert_eq!(filtered.len(), count);
+                    kept += filtered.len();
+                }
+
+                // every candidate matches exactly one hint, its own
+                assert_eq!(kept, passwords.len());
+                for secret in &passwords {
+                    assert!(secret.matches_description(
+                        guess,
+                        guess.hint(secret, feedback),
+                        feedback
+                    ));
+                }
+            }
+        }
+
+        let secret = "rgb".parse::<Password<3>>().unwrap();
+        let guess = "rgg".parse::<Password<3>>().unwrap();
+        assert!(secret.matches_description(&guess, 2, FeedbackMode::Positional));
+        assert!(!secret.matches_description(&guess, 1, FeedbackMode::Positional));
+    }
+
+    #[test]
+    fn count_color_counts_every_position() {
+        let password = "rgbrr".parse::<Password<5>>().unwrap();
+
+        assert_eq!(password.count_color(Color::Red), 3);
+        assert_eq!(password.count_color(Color::Green), 1);
+        assert_eq!(password.count_color(Color::Blue), 1);
+        assert_eq!(password.count_color(Color::Yellow), 0);
+
+        for password in all::<3>() {
+            let counts = Color::all().map(|color| password.count_color(color));
+            assert_eq!(counts.iter().sum::<usize>(), 3);
+        }
+    }
+
+    #[test]
+    fn is_valid_for_checks_the_palette_size() {
+        let parse = |abbrev: &str| abbrev.parse::<Password<5>>().unwrap();
+
+        assert!(parse("rgbrg").is_valid_for(COLORS));
+        assert!(parse("rgbrg").is_valid_for(3));
+        assert!(!parse("rgbrg").is_valid_for(2));
+
+        assert!(parse("rgbyg").is_valid_for(COLORS));
+        assert!(!parse("rgbyg").is_valid_for(3));
+
+        assert!(all::<3>()
+            .iter()
+            .all(|password| password.is_valid_for(COLORS)));
+    }
+
+    #[test]
+    fn distance_profile_counts_each_distance() {
+        let parse = |abbrev: &str| abbrev.parse::<Password<5>>().unwrap();
+        let password = parse("rgbyr");
+        // distances 0, 1, 3 and 3
+        let set = ["rgbyr", "rgbyg", "rgggg", "yyyyr"].map(parse);
+
+        assert_eq!(password.distance_profile(&set), [1, 1, 0, 2, 0, 0]);
+
+        let mut profile = Vec::with_capacity(16);
+        profile.extend([7; 9]);
+        let buffer = profile.as_ptr();
+        password.distance_profile_into(&set, &mut profile);
+        assert_eq!(profile, [1, 1, 0, 2, 0, 0]);
+        assert_eq!(profile.as_ptr(), buffer);
+
+        let passwords = all::<3>();
+        for password in &passwords {
+            let distribution = password.hint_distribution(&passwords, FeedbackMode::Positional);
+            let profile = password.distance_profile(&passwords);
+            assert!((0..=3).all(|d| profile[d] == distribution[3 - d]));
+        }
+    }
+
+    #[test]
+    fn generating_from_a_seed_is_reproducible() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let weights = ColorWeights::uniform();
+        let draw = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (
+                Password::<5>::generate_with(&mut rng),
+                Password::<5>::generate_weighted_with(&weights, &mut rng),
+            )
+        };
+
+        assert_eq!(draw(3), draw(3));
+        assert!((0..8).map(draw).unique().count() > 1);
+    }
+}
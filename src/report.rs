@@ -0,0 +1,31 @@
+use crate::{Histogram, Password};
+
+/// Statistics of a `solve_all` run, for printing or exporting without
+/// redoing the solves.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SolveReport<const N: usize> {
+    /// Number of problems solved.
+    pub total: usize,
+    pub average: f64,
+    pub median: f64,
+    pub stddev: f64,
+    /// The problem that took the most guesses, and how many.
+    pub worst_case: Option<(Password<N>, usize)>,
+    pub histogram: Histogram,
+}
+
+impl<const N: usize> SolveReport<N> {
+    /// The report of `histogram`, whose problem indices are into `candidates`.
+    pub fn new(histogram: Histogram, candidates: &[Password<N>]) -> Self {
+        Self {
+            total: histogram.problems(),
+            average: histogram.average(),
+            median: histogram.median(),
+            stddev: histogram.stddev(),
+            worst_case: histogram
+                .worst_case()
+                .map(|(index, guesses)| (candidates[index].clone(), guesses)),
+            histogram,
+        }
+    }
+}
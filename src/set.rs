@@ -0,0 +1,135 @@
+use std::ops::Deref;
+
+use itertools::Itertools;
+
+use crate::{
+    filter_candidates, score_guesses, Color, FeedbackMode, GuessStrategy, Password, PasswordError,
+};
+
+/// A set of candidate passwords, e.g. the secrets still possible on a board.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PasswordSet<const N: usize> {
+    passwords: Vec<Password<N>>,
+}
+
+impl<const N: usize> PasswordSet<{ N }> {
+    pub fn new(passwords: Vec<Password<N>>) -> Self {
+        Self { passwords }
+    }
+
+    /// A set of `passwords` made only of the colors in `palette`. Any other
+    /// color can never be in a problem set built from `palette`, and would
+    /// throw off every partition the set is scored against.
+    pub fn with_palette(
+        passwords: Vec<Password<N>>,
+        palette: &[Color],
+    ) -> Result<Self, PasswordError> {
+        match passwords
+            .iter()
+            .find_map(|password| password.color_outside(palette))
+        {
+            Some(color) => Err(PasswordError::OutsidePalette(color)),
+            None => Ok(Self::new(passwords)),
+        }
+    }
+
+    /// The passwords of this set that give `hint` against `guess`.
+    pub fn filter(&self, guess: &Password<N>, hint: usize, feedback: FeedbackMode) -> Self {
+        Self::new(filter_candidates(&self.passwords, guess, hint, feedback))
+    }
+
+    /// Every guess of `guesses` with its `strategy` score against this set,
+    /// best first. Equal scores keep the order of `guesses`.
+    pub fn entropy_all_parallel<S: GuessStrategy<N>>(
+        &self,
+        guesses: &[Password<N>],
+        strategy: &S,
+        feedback: FeedbackMode,
+    ) -> Vec<(Password<N>, f64)> {
+        let scores = score_guesses(guesses, &self.passwords, strategy, feedback);
+
+        guesses
+            .iter()
+            .cloned()
+            .zip(scores)
+            .sorted_by(|(_, a), (_, b)| S::better(*b, *a))
+            .collect()
+    }
+
+    pub fn into_vec(self) -> Vec<Password<N>> {
+        self.passwords
+    }
+}
+
+impl<const N: usize> Deref for PasswordSet<N> {
+    type Target = [Password<N>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.passwords
+    }
+}
+
+impl<const N: usize> From<Vec<Password<N>>> for PasswordSet<N> {
+    fn from(passwords: Vec<Password<N>>) -> Self {
+        Self::new(passwords)
+    }
+}
+
+impl<const N: usize> FromIterator<Password<N>> for PasswordSet<N> {
+    fn from_iter<I: IntoIterator<Item = Password<N>>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Entropy, Minimax, SolverConfig};
+
+    #[test]
+    fn entropy_all_parallel_ranks_every_guess_best_first() {
+        let guesses = SolverConfig::<3, Entropy>::default().problem_set();
+        let feedback = FeedbackMode::Positional;
+        // against every password all guesses tie, so score a narrowed set
+        let set = PasswordSet::new(guesses.clone()).filter(&"rgb".parse().unwrap(), 1, feedback);
+
+        let ranked = set.entropy_all_parallel(&guesses, &Entropy, feedback);
+        assert_eq!(ranked.len(), guesses.len());
+        assert!(ranked.iter().map(|(guess, _)| guess).all_unique());
+        for (guess, score) in &ranked {
+            assert_eq!(*score, Entropy.score_against(guess, &set, feedback));
+        }
+        assert!(ranked.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert!(ranked[0].1 > ranked[ranked.len() - 1].1);
+
+        // smaller is better for minimax
+        let ranked = set.entropy_all_parallel(&guesses, &Minimax, feedback);
+        for (guess, score) in &ranked {
+            assert_eq!(*score, Minimax.score_against(guess, &set, feedback));
+        }
+        assert!(ranked.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+    }
+
+    #[test]
+    fn with_palette_keeps_only_passwords_of_the_palette() {
+        let palette = [Color::Red, Color::Blue];
+        let config = SolverConfig::<3, Entropy> {
+            palette: palette.to_vec(),
+            ..Default::default()
+        };
+
+        let set = PasswordSet::with_palette(config.problem_set(), &palette).unwrap();
+        assert_eq!(set.len(), 8);
+        assert!(set.iter().all_unique());
+        assert!(set
+            .iter()
+            .all(|password| password.color_outside(&palette).is_none()));
+
+        let mut passwords = set.into_vec();
+        passwords.push("rgb".parse().unwrap());
+        assert!(matches!(
+            PasswordSet::with_palette(passwords, &palette),
+            Err(PasswordError::OutsidePalette(Color::Green))
+        ));
+    }
+}
@@ -0,0 +1,1042 @@
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use itertools::Itertools;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+use crate::{
+    entropy, expected_gain, Color, FeedbackMode, GuessStrategy, Oracle, Password, PasswordSet,
+    ScoreCache,
+};
+
+/// A single guess made by the solver and the feedback it received.
+#[derive(Clone, Debug)]
+pub struct SolveStep<const N: usize> {
+    pub guess: Password<N>,
+    /// `entropy` of the hint partition `guess` made of the candidates.
+    pub entropy: f64,
+    /// `expected_gain` of the same partition.
+    pub expected_gain: f64,
+    pub hint: usize,
+    pub remaining: usize,
+}
+
+/// Every guess of a solve, rendered as a game board by `Display`.
+#[derive(Clone, Debug)]
+pub struct SolvePath<const N: usize> {
+    steps: Vec<SolveStep<N>>,
+    solution: Option<Password<N>>,
+    /// Candidates left when the solve finished.
+    remaining: usize,
+    timed_out: bool,
+    feedback: FeedbackMode,
+}
+
+impl<const N: usize> SolvePath<{ N }> {
+    pub fn steps(&self) -> &[SolveStep<N>] {
+        &self.steps
+    }
+
+    /// The single candidate left once the solve finished.
+    pub fn solution(&self) -> Option<&Password<N>> {
+        self.solution.as_ref()
+    }
+
+    pub fn feedback(&self) -> FeedbackMode {
+        self.feedback
+    }
+
+    /// Every guess made, in order, as `Password::abbrev` strings.
+    pub fn abbrevs(&self) -> Vec<String> {
+        self.steps.iter().map(|step| step.guess.abbrev()).collect()
+    }
+
+    /// Number of guesses the solver had to make before one candidate remained.
+    pub fn guesses(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Number of candidates left when the solve finished, 1 if it was solved.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Whether the solve stopped at `SolveOptions::timeout`. The steps made
+    /// until then are kept, the last one being the best guess so far.
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+}
+
+impl<const N: usize> std::fmt::Display for SolvePath<{ N }> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut rows = self
+            .steps
+            .iter()
+            .map(|step| {
+                let hint = self.feedback.decode::<N>(step.hint).to_string();
+                (step.guess.to_string(), hint, step.remaining)
+            })
+            .collect_vec();
+
+        // the deduced solution is only shown if it wasn't already guessed
+        let solved = self.feedback.solved::<N>();
+        if let Some(solution) = &self.solution {
+            if self.steps.last().is_none_or(|step| step.hint != solved) {
+                let hint = self.feedback.decode::<N>(solved).to_string();
+                rows.push((solution.to_string(), hint, 1));
+            }
+        }
+
+        let turn_width = rows.len().to_string().len();
+        let guess_width = rows
+            .iter()
+            .map(|(guess, _, _)| guess.len())
+            .max()
+            .unwrap_or(0);
+        let hint_width = rows
+            .iter()
+            .map(|(_, hint, _)| hint.len())
+            .max()
+            .unwrap_or(0);
+        let remaining_width = rows
+            .iter()
+            .map(|(_, _, remaining)| remaining.to_string().len())
+            .max()
+            .unwrap_or(0);
+
+        for (turn, (guess, hint, remaining)) in rows.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>turn_width$}. {guess:<guess_width$} | {hint:>hint_width$} | {remaining:>remaining_width$} remaining",
+                turn + 1
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SolveOptions {
+    pub print_steps: bool,
+    /// Only score this many randomly sampled guesses (plus every remaining
+    /// candidate) per step instead of the whole problem set.
+    pub sample_guesses: Option<usize>,
+    /// Have `solve_automatically` sample guesses whenever scoring every
+    /// guess against every candidate would take more than this many hint
+    /// computations.
+    pub max_set_size: Option<usize>,
+    /// Score only one guess per distinct partition of candidate sets of at
+    /// most this size, see `partition_representatives`.
+    pub dedupe_guesses: Option<usize>,
+    pub tie_break: TieBreak,
+    /// Give up a solve, with a warning, after this many guesses.
+    pub max_steps: Option<usize>,
+    /// Stop a solve, with a warning, once it has run this long. Checked
+    /// between guesses, so the guess being scored is still finished.
+    pub timeout: Option<Duration>,
+    pub seed: u64,
+    pub feedback: FeedbackMode,
+    /// Among guesses scoring within `NEAR_EQUAL` of the best, pick one with
+    /// the most distinct colors.
+    pub prefer_diverse: bool,
+    /// Keep candidates contradicting at most this many hints, for games
+    /// where hints may be lies. See `filter_with_lies`.
+    pub lies: usize,
+}
+
+impl SolveOptions {
+    /// These options for solving problem `index` of a whole run, seeded by
+    /// the index rather than by where the problem comes in the solving
+    /// order, so that a shuffled run reports the same stats.
+    pub fn for_problem(self, index: usize) -> Self {
+        Self {
+            seed: self.seed.wrapping_add(index as u64),
+            ..self
+        }
+    }
+}
+
+/// Which of several equally scored guesses is made.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, ValueEnum)]
+pub enum TieBreak {
+    /// The last tied guess in problem-set order
+    #[default]
+    Any,
+    /// The smallest by color index, position by position
+    Lexicographic,
+    /// The smallest `Password::to_code`
+    Code,
+    /// A random one, drawn from the seed
+    Random,
+}
+
+/// Relative score difference under which `prefer_diverse` treats two
+/// guesses as equally good.
+const NEAR_EQUAL: f64 = 1e-9;
+
+/// The score of every guess of `guesses` against `answer_set`, in the same
+/// order, computed in parallel.
+pub fn score_guesses<const N: usize, S: GuessStrategy<N>>(
+    guesses: &[Password<N>],
+    answer_set: &[Password<N>],
+    strategy: &S,
+    feedback: FeedbackMode,
+) -> Vec<f64> {
+    guesses
+        .par_iter()
+        .map(|comb| strategy.score_against(comb, answer_set, feedback))
+        .collect()
+}
+
+/// The guess of `guesses` that `strategy` scores best against `answer_set`.
+pub fn choose_guess<const N: usize, S: GuessStrategy<N>>(
+    guesses: &[Password<N>],
+    answer_set: &[Password<N>],
+    strategy: &S,
+    options: &SolveOptions,
+) -> Password<N> {
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    choose_guess_cached(guesses, answer_set, strategy, options, None, &mut rng)
+}
+
+/// `choose_guess`, taking the scores from `cache` when it has them and
+/// drawing `TieBreak::Random` ties from `rng`, so that a whole solve can
+/// share one generator.
+pub fn choose_guess_cached<const N: usize, S: GuessStrategy<N>>(
+    guesses: &[Password<N>],
+    answer_set: &[Password<N>],
+    strategy: &S,
+    options: &SolveOptions,
+    cache: Option<&ScoreCache<N>>,
+    rng: &mut StdRng,
+) -> Password<N> {
+    let guesses = match options.dedupe_guesses {
+        // other tie-breaks may want a guess that isn't its partition's last
+        Some(max) if answer_set.len() <= max && options.tie_break == TieBreak::Any => Cow::Owned(
+            partition_representatives(guesses, answer_set, options.feedback),
+        ),
+        _ => Cow::Borrowed(guesses),
+    };
+
+    let scores = match cache {
+        Some(cache) => cache.scores(&guesses, answer_set, |comb| {
+            strategy.score_against(comb, answer_set, options.feedback)
+        }),
+        None => score_guesses(&guesses, answer_set, strategy, options.feedback),
+    };
+
+    // `better` orders NaN consistently, but a NaN score means a strategy
+    // divided by an empty partition and its choice is meaningless
+    debug_assert!(
+        scores.iter().all(|score| !score.is_nan()),
+        "a guess scored NaN against {} candidates",
+        answer_set.len()
+    );
+
+    let (best, best_score) = scores
+        .par_iter()
+        .enumerate()
+        .max_by(|(_, score_a), (_, score_b)| S::better(**score_a, **score_b))
+        .unwrap();
+
+    // the parallel reduction must agree with a plain scan over every score
+    debug_assert!(
+        scores
+            .iter()
+            .all(|score| S::better(*score, *best_score) != Ordering::Greater),
+        "chosen guess {} doesn't have the best score",
+        guesses[best]
+    );
+
+    if options.tie_break == TieBreak::Any && !options.prefer_diverse {
+        return guesses[best].clone();
+    }
+
+    let tied = if options.prefer_diverse {
+        let tolerance = NEAR_EQUAL * best_score.abs().max(1.0);
+        let near = (0..guesses.len())
+            .filter(|&i| (scores[i] - best_score).abs() <= tolerance)
+            .collect_vec();
+        let most = near
+            .iter()
+            .map(|&i| guesses[i].distinct_colors())
+            .max()
+            .unwrap();
+
+        near.into_iter()
+            .filter(|&i| guesses[i].distinct_colors() == most)
+            .collect_vec()
+    } else {
+        (0..guesses.len())
+            .filter(|&i| S::better(scores[i], *best_score) == Ordering::Equal)
+            .collect_vec()
+    };
+
+    let chosen = match options.tie_break {
+        TieBreak::Any => *tied.last().unwrap(),
+        TieBreak::Lexicographic => *tied.iter().min_by_key(|&&i| &guesses[i]).unwrap(),
+        TieBreak::Code => *tied.iter().min_by_key(|&&i| guesses[i].to_code()).unwrap(),
+        TieBreak::Random => *tied.choose(rng).unwrap(),
+    };
+
+    guesses[chosen].clone()
+}
+
+/// One guess of `guesses` per distinct partition of `answer_set`, which
+/// every strategy scores the same. The last guess of each partition is kept,
+/// in order, so ties still resolve to the same guess.
+///
+/// Finding the partitions costs as much as scoring by any of the current
+/// strategies, so this only saves time for scores that are expensive on
+/// their own.
+pub fn partition_representatives<const N: usize>(
+    guesses: &[Password<N>],
+    answer_set: &[Password<N>],
+    feedback: FeedbackMode,
+) -> Vec<Password<N>> {
+    let signatures = guesses
+        .par_iter()
+        .map(|guess| {
+            answer_set
+                .iter()
+                .map(|answer| guess.hint(answer, feedback))
+                .collect_vec()
+        })
+        .collect::<Vec<_>>();
+
+    let mut last = HashMap::new();
+    for (i, signature) in signatures.into_iter().enumerate() {
+        last.insert(signature, i);
+    }
+
+    last.into_values()
+        .sorted_unstable()
+        .map(|i| guesses[i].clone())
+        .collect()
+}
+
+/// Guesses until one candidate is left, taking each hint from `oracle`.
+/// Stops early, without a solution, if the oracle runs out of hints, a
+/// guess leaves every candidate or `max_steps` is reached.
+pub fn solve_automatically<const N: usize, S: GuessStrategy<N>>(
+    problem_set: &[Password<N>],
+    candidates: &[Password<N>],
+    oracle: &mut dyn Oracle<N>,
+    strategy: &S,
+    options: SolveOptions,
+    cache: Option<&ScoreCache<N>>,
+) -> SolvePath<N> {
+    let start = Instant::now();
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let mut answer_set = candidates.to_vec();
+    let mut tallies = candidates
+        .iter()
+        .map(|candidate| (candidate.clone(), 0))
+        .collect_vec();
+    let mut steps = vec![];
+    let mut timed_out = false;
+    while answer_set.len() > 1 {
+        if let Some(timeout) = options
+            .timeout
+            .filter(|&timeout| start.elapsed() >= timeout)
+        {
+            eprintln!(
+                "warning: timed out after {timeout:?} with {} candidates left",
+                answer_set.len()
+            );
+            timed_out = true;
+            break;
+        }
+
+        if let Some(max_steps) = options.max_steps.filter(|&max| steps.len() >= max) {
+            eprintln!(
+                "warning: giving up after {max_steps} guesses with {} candidates left",
+                answer_set.len()
+            );
+            break;
+        }
+
+        let sample_guesses = options.sample_guesses.or_else(|| {
+            let max_set_size = options.max_set_size?;
+            let work = problem_set.len() * answer_set.len();
+            (work > max_set_size).then(|| {
+                let sample_size = (max_set_size / answer_set.len()).max(1);
+                eprintln!(
+                    "warning: scoring {} guesses against {} candidates exceeds the limit of {max_set_size}, sampling {sample_size} guesses",
+                    problem_set.len(),
+                    answer_set.len()
+                );
+                sample_size
+            })
+        });
+
+        let guesses = match sample_guesses {
+            Some(sample_size) => {
+                let mut guesses = problem_set
+                    .choose_multiple(&mut rng, sample_size)
+                    .cloned()
+                    .collect_vec();
+                guesses.extend(answer_set.iter().cloned());
+                Cow::Owned(guesses)
+            }
+            None => Cow::Borrowed(problem_set),
+        };
+
+        let answer =
+            choose_guess_cached(&guesses, &answer_set, strategy, &options, cache, &mut rng);
+        let distribution = answer.hint_distribution(&answer_set, options.feedback);
+        let entropy = entropy(&distribution);
+        let expected_gain = expected_gain(&distribution);
+
+        let Some(hint) = oracle.feedback(&answer) else {
+            break;
+        };
+        let before = answer_set.len();
+        let violations_before = tallies
+            .iter()
+            .map(|(_, violations)| violations)
+            .sum::<usize>();
+        tallies = filter_with_lies(&tallies, &answer, hint, options.feedback, options.lies);
+        answer_set = tallies
+            .iter()
+            .map(|(candidate, _)| candidate.clone())
+            .collect();
+
+        // a guess that can't shrink the set or count against any candidate
+        // would be chosen again forever
+        let violations = tallies
+            .iter()
+            .map(|(_, violations)| violations)
+            .sum::<usize>();
+        let stalled = answer_set.len() == before && violations == violations_before;
+        if stalled {
+            eprintln!(
+                "warning: {} didn't narrow down the {before} candidates, giving up",
+                answer.abbrev()
+            );
+        }
+
+        steps.push(SolveStep {
+            guess: answer,
+            entropy,
+            expected_gain,
+            hint,
+            remaining: answer_set.len(),
+        });
+        if stalled {
+            break;
+        }
+    }
+
+    let path = SolvePath {
+        solution: match answer_set.as_slice() {
+            [solution] => Some(solution.clone()),
+            _ => None,
+        },
+        steps,
+        remaining: answer_set.len(),
+        timed_out,
+        feedback: options.feedback,
+    };
+
+    if options.print_steps {
+        print!("{path}");
+    }
+
+    path
+}
+
+/// The candidates that give `hint` against `guess`.
+pub fn filter_candidates<const N: usize>(
+    candidates: &[Password<N>],
+    guess: &Password<N>,
+    hint: usize,
+    feedback: FeedbackMode,
+) -> Vec<Password<N>> {
+    candidates
+        .iter()
+        .filter(|candidate| candidate.matches_description(guess, hint, feedback))
+        .cloned()
+        .collect()
+}
+
+/// How a game is set up, for embedding a `Solver` without the CLI. The
+/// password length is the `N` of the type.
+#[derive(Clone, Debug)]
+pub struct SolverConfig<const N: usize, S> {
+    /// Colors the passwords are made of; repeated colors are ignored.
+    pub palette: Vec<Color>,
+    pub strategy: S,
+    pub feedback: FeedbackMode,
+    pub seed: u64,
+}
+
+impl<const N: usize, S: Default> Default for SolverConfig<N, S> {
+    fn default() -> Self {
+        Self {
+            palette: Color::all().to_vec(),
+            strategy: S::default(),
+            feedback: FeedbackMode::default(),
+            seed: 0,
+        }
+    }
+}
+
+impl<const N: usize, S> SolverConfig<N, S> {
+    /// Every password made of the palette's colors, in lexicographic order.
+    pub fn problem_set(&self) -> Vec<Password<N>> {
+        let palette = self.palette.iter().copied().unique().collect_vec();
+
+        (0..N)
+            .map(|_| palette.iter().copied())
+            .multi_cartesian_product()
+            .map(|colors| Password::new(&colors))
+            .collect()
+    }
+
+    /// `SolveOptions` with this feedback mode and seed, and no other option.
+    pub fn options(&self) -> SolveOptions {
+        SolveOptions {
+            feedback: self.feedback,
+            seed: self.seed,
+            ..SolveOptions::default()
+        }
+    }
+}
+
+/// Counts one more violation for every candidate of `tallies` that doesn't
+/// give `hint` against `guess`, dropping the ones with more than `lies`.
+/// The solved hint is never a lie, so it leaves only `guess`. With no lies
+/// this keeps the same candidates as `filter_candidates`.
+pub fn filter_with_lies<const N: usize>(
+    tallies: &[(Password<N>, usize)],
+    guess: &Password<N>,
+    hint: usize,
+    feedback: FeedbackMode,
+    lies: usize,
+) -> Vec<(Password<N>, usize)> {
+    if hint == feedback.solved::<N>() {
+        return tallies
+            .iter()
+            .filter(|(candidate, _)| candidate == guess)
+            .cloned()
+            .collect();
+    }
+
+    tallies
+        .iter()
+        .filter_map(|(candidate, violations)| {
+            let violations =
+                violations + usize::from(!candidate.matches_description(guess, hint, feedback));
+            (violations <= lies).then(|| (candidate.clone(), violations))
+        })
+        .collect()
+}
+
+/// Solver state for front-ends that drive a game themselves.
+#[derive(Clone, Debug)]
+pub struct Solver<const N: usize, S> {
+    problem_set: Vec<Password<N>>,
+    candidates: Vec<Password<N>>,
+    strategy: S,
+    options: SolveOptions,
+}
+
+impl<const N: usize, S: GuessStrategy<N>> Solver<N, S> {
+    /// Starts a game where every password of `problem_set` may be the secret.
+    pub fn new(problem_set: Vec<Password<N>>, strategy: S, options: SolveOptions) -> Self {
+        Self {
+            candidates: problem_set.clone(),
+            problem_set,
+            strategy,
+            options,
+        }
+    }
+
+    /// Starts a game as set up by `config`, where every password of its
+    /// palette may be the secret.
+    pub fn with_config(config: SolverConfig<N, S>) -> Self {
+        let (problem_set, options) = (config.problem_set(), config.options());
+        Self::new(problem_set, config.strategy, options)
+    }
+
+    /// Starts a game where the secret is one of `candidates`, guessing from
+    /// `problem_set`.
+    pub fn with_candidates(
+        problem_set: Vec<Password<N>>,
+        candidates: Vec<Password<N>>,
+        strategy: S,
+        options: SolveOptions,
+    ) -> Self {
+        Self {
+            problem_set,
+            candidates,
+            strategy,
+            options,
+        }
+    }
+
+    /// Keeps only the candidates that give `hint` against `guess`.
+    pub fn apply(&mut self, guess: &Password<N>, hint: usize) {
+        self.candidates = filter_candidates(&self.candidates, guess, hint, self.options.feedback);
+    }
+
+    /// Only one candidate is left.
+    pub fn is_solved(&self) -> bool {
+        self.candidates.len() == 1
+    }
+
+    /// The secret, once it is the only candidate left.
+    pub fn solution(&self) -> Option<Password<N>> {
+        match self.candidates.as_slice() {
+            [solution] => Some(solution.clone()),
+            _ => None,
+        }
+    }
+
+    /// The guess the strategy recommends against the current candidates, or
+    /// `None` once there is nothing left to narrow down.
+    pub fn next_guess(&self) -> Option<Password<N>> {
+        match self.candidates.len() {
+            0 => None,
+            1 => self.solution(),
+            _ => Some(choose_guess(
+                &self.problem_set,
+                &self.candidates,
+                &self.strategy,
+                &self.options,
+            )),
+        }
+    }
+
+    /// The current candidates that are also consistent with every
+    /// `(guess, hint)` of `history`, sorted.
+    pub fn remaining_candidates(&self, history: &[(Password<N>, usize)]) -> Vec<Password<N>> {
+        let mut remaining =
+            history
+                .iter()
+                .fold(self.candidates.clone(), |candidates, (guess, hint)| {
+                    filter_candidates(&candidates, guess, *hint, self.options.feedback)
+                });
+        remaining.sort_unstable();
+
+        remaining
+    }
+}
+
+/// Several boards sharing every guess, each with its own secret.
+#[derive(Clone, Debug)]
+pub struct MultiSolver<const N: usize> {
+    problem_set: Vec<Password<N>>,
+    boards: Vec<PasswordSet<N>>,
+    feedback: FeedbackMode,
+}
+
+impl<const N: usize> MultiSolver<N> {
+    /// Starts `boards` games where every password of `problem_set` may be
+    /// the secret.
+    pub fn new(problem_set: Vec<Password<N>>, boards: usize, feedback: FeedbackMode) -> Self {
+        Self {
+            boards: vec![PasswordSet::new(problem_set.clone()); boards],
+            problem_set,
+            feedback,
+        }
+    }
+
+    pub fn boards(&self) -> &[PasswordSet<N>] {
+        &self.boards
+    }
+
+    /// Every board is down to at most one candidate.
+    pub fn is_solved(&self) -> bool {
+        self.boards.iter().all(|board| board.len() <= 1)
+    }
+
+    /// The guess maximizing the entropy summed over every board.
+    pub fn next_guess(&self) -> Option<Password<N>> {
+        if self.is_solved() {
+            return None;
+        }
+
+        self.problem_set
+            .par_iter()
+            .map(|guess| {
+                let total = self
+                    .boards
+                    .iter()
+                    .map(|board| guess.entropy_only(board.iter(), self.feedback))
+                    .sum::<f64>();
+                (guess, total)
+            })
+            .max_by(|(_, total_a), (_, total_b)| total_a.total_cmp(total_b))
+            .map(|(guess, _)| guess.clone())
+    }
+
+    /// Narrows every board down with the hint it gave for `guess`, in board
+    /// order.
+    pub fn apply(&mut self, guess: &Password<N>, hints: &[usize]) {
+        assert_eq!(
+            hints.len(),
+            self.boards.len(),
+            "expected one hint per board"
+        );
+
+        for (board, hint) in self.boards.iter_mut().zip(hints) {
+            *board = board.filter(guess, *hint, self.feedback);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Entropy, Histogram, KnownSecret};
+
+    /// The board `explain` and `print_steps` show for the worst case of the
+    /// default configuration. Update it only for an intended change.
+    #[test]
+    fn solve_path_board_is_stable() {
+        let config = SolverConfig::<5, Entropy>::default();
+        let problem_set = config.problem_set();
+        let secret = "yrryr".parse::<Password<5>>().unwrap();
+
+        let path = solve_automatically(
+            &problem_set,
+            &problem_set,
+            &mut KnownSecret {
+                secret,
+                feedback: config.feedback,
+            },
+            &config.strategy,
+            config.options(),
+            None,
+        );
+
+        let golden = "\
+            1. [Yellow, Yellow, Yellow, Yellow, Yellow] | 2 hits | 270 remaining\n\
+            2. [Yellow, Yellow, Blue, Blue, Blue]       | 1 hits |  99 remaining\n\
+            3. [Blue, Blue, Yellow, Yellow, Blue]       | 1 hits |  48 remaining\n\
+            4. [Yellow, Blue, Green, Green, Yellow]     | 1 hits |  25 remaining\n\
+            5. [Yellow, Green, Yellow, Red, Green]      | 1 hits |  10 remaining\n\
+            6. [Green, Yellow, Yellow, Green, Red]      | 1 hits |   5 remaining\n\
+            7. [Yellow, Yellow, Blue, Yellow, Green]    | 2 hits |   2 remaining\n\
+            8. [Yellow, Yellow, Yellow, Yellow, Blue]   | 2 hits |   1 remaining\n\
+            9. [Yellow, Red, Red, Yellow, Red]          | 5 hits |   1 remaining\n";
+
+        assert_eq!(path.to_string(), golden);
+    }
+
+    /// `(secret, guesses)` of every length-4 secret solved with `options`.
+    fn length_4_solve_all(options: SolveOptions) -> Vec<(String, usize)> {
+        let problem_set = SolverConfig::<4, Entropy>::default().problem_set();
+
+        problem_set
+            .iter()
+            .map(|secret| {
+                let mut oracle = KnownSecret {
+                    secret: secret.clone(),
+                    feedback: options.feedback,
+                };
+                let path = solve_automatically(
+                    &problem_set,
+                    &problem_set,
+                    &mut oracle,
+                    &Entropy,
+                    options,
+                    None,
+                );
+                (secret.abbrev(), path.guesses())
+            })
+            .collect()
+    }
+
+    /// The average and the last of the worst secrets.
+    fn average_and_worst(guesses: &[(String, usize)]) -> (f64, (&str, usize)) {
+        let total = guesses.iter().map(|(_, guesses)| guesses).sum::<usize>();
+        let (secret, worst) = guesses.iter().max_by_key(|(_, guesses)| guesses).unwrap();
+
+        (total as f64 / guesses.len() as f64, (secret, *worst))
+    }
+
+    /// Golden values of a whole length-4 run as `solve-all` plays it. Any
+    /// change to how guesses are chosen, down to how ties are broken, shows
+    /// up here and needs an intended update.
+    #[test]
+    fn length_4_solve_all_is_stable() {
+        let guesses = length_4_solve_all(SolverConfig::<4, Entropy>::default().options());
+
+        assert_eq!(guesses.len(), 256);
+        assert_eq!(average_and_worst(&guesses), (4.609375, ("ygbr", 6)));
+    }
+
+    /// `TieBreak::Code` plays other games but needs as many guesses in all.
+    #[test]
+    fn length_4_solve_all_by_code_is_stable() {
+        let options = SolveOptions {
+            tie_break: TieBreak::Code,
+            ..SolverConfig::<4, Entropy>::default().options()
+        };
+        let guesses = length_4_solve_all(options);
+
+        assert_eq!(average_and_worst(&guesses), (4.609375, ("yybg", 6)));
+    }
+
+    /// A single position leaves nothing to deduce but the color itself, so
+    /// the four secrets take 1, 2, 3 and 3 guesses.
+    #[test]
+    fn length_1_solve_all_averages_2_25() {
+        let config = SolverConfig::<1, Entropy>::default();
+        let problem_set = config.problem_set();
+        let options = config.options();
+
+        let total = problem_set
+            .iter()
+            .map(|secret| {
+                let mut oracle = KnownSecret {
+                    secret: secret.clone(),
+                    feedback: options.feedback,
+                };
+                solve_automatically(
+                    &problem_set,
+                    &problem_set,
+                    &mut oracle,
+                    &config.strategy,
+                    options,
+                    None,
+                )
+                .guesses()
+            })
+            .sum::<usize>();
+
+        assert_eq!(problem_set.len(), 4);
+        assert_eq!(total as f64 / 4.0, 2.25);
+    }
+
+    #[test]
+    fn chosen_guess_maximizes_entropy() {
+        let config = SolverConfig::<3, Entropy>::default();
+        let problem_set = config.problem_set();
+        let options = config.options();
+
+        // the whole set, and the candidates left after a couple of hints
+        let rrg = "rrg".parse::<Password<3>>().unwrap();
+        let byb = "byb".parse::<Password<3>>().unwrap();
+        let after_one = filter_candidates(&problem_set, &rrg, 1, options.feedback);
+        let after_two = filter_candidates(&after_one, &byb, 1, options.feedback);
+
+        for answer_set in [&problem_set, &after_one, &after_two] {
+            let best = problem_set
+                .iter()
+                .map(|guess| entropy(&guess.hint_distribution(answer_set, options.feedback)))
+                .fold(f64::NEG_INFINITY, f64::max);
+
+            for tie_break in TieBreak::value_variants() {
+                let options = SolveOptions {
+                    tie_break: *tie_break,
+                    ..options
+                };
+                let chosen = choose_guess(&problem_set, answer_set, &Entropy, &options);
+                let chosen_entropy =
+                    entropy(&chosen.hint_distribution(answer_set, options.feedback));
+
+                assert_eq!(
+                    chosen_entropy,
+                    best,
+                    "{chosen} with {tie_break:?} against {} candidates",
+                    answer_set.len()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn solver_plays_every_game_to_the_end() {
+        let config = SolverConfig::<3, Entropy>::default();
+        let problem_set = config.problem_set();
+        let options = config.options();
+
+        for secret in &problem_set {
+            let mut solver = Solver::with_config(SolverConfig::<3, Entropy>::default());
+            let mut guesses = 0;
+
+            while !solver.is_solved() {
+                assert_eq!(solver.solution(), None);
+
+                let guess = solver.next_guess().unwrap();
+                solver.apply(&guess, secret.hint(&guess, options.feedback));
+                guesses += 1;
+            }
+
+            assert_eq!(solver.solution().as_ref(), Some(secret));
+            assert_eq!(solver.next_guess().as_ref(), Some(secret));
+
+            // the same game as the automatic solver plays
+            let path = solve_automatically(
+                &problem_set,
+                &problem_set,
+                &mut KnownSecret {
+                    secret: secret.clone(),
+                    feedback: options.feedback,
+                },
+                &Entropy,
+                options,
+                None,
+            );
+            assert_eq!(guesses, path.guesses(), "{secret}");
+        }
+    }
+
+    #[test]
+    fn solver_without_candidates_has_no_guess() {
+        let mut solver = Solver::with_config(SolverConfig::<3, Entropy>::default());
+        let guess = "rgb".parse::<Password<3>>().unwrap();
+
+        // no secret gives the solved hint and then another one
+        solver.apply(&guess, 3);
+        solver.apply(&guess, 0);
+
+        assert!(!solver.is_solved());
+        assert_eq!(solver.solution(), None);
+        assert_eq!(solver.next_guess(), None);
+    }
+
+    /// `solve-all --shuffle` must report the same stats. Both take the options
+    /// of each solve from `SolveOptions::for_problem`.
+    #[test]
+    fn stats_do_not_depend_on_the_solving_order() {
+        let config = SolverConfig::<3, Entropy>::default();
+        let problem_set = config.problem_set();
+        let options = SolveOptions {
+            tie_break: TieBreak::Random,
+            sample_guesses: Some(8),
+            ..config.options()
+        };
+
+        let histogram = |order: &[usize]| {
+            order
+                .iter()
+                .map(|&i| {
+                    let options = options.for_problem(i);
+                    let mut oracle = KnownSecret {
+                        secret: problem_set[i].clone(),
+                        feedback: options.feedback,
+                    };
+                    let path = solve_automatically(
+                        &problem_set,
+                        &problem_set,
+                        &mut oracle,
+                        &Entropy,
+                        options,
+                        None,
+                    );
+                    (i, path.guesses())
+                })
+                .collect::<Histogram>()
+        };
+
+        let in_order = (0..problem_set.len()).collect_vec();
+        let mut shuffled = in_order.clone();
+        shuffled.shuffle(&mut StdRng::seed_from_u64(7));
+        let reversed = in_order.iter().rev().copied().collect_vec();
+
+        let expected = histogram(&in_order);
+        assert_eq!(histogram(&shuffled), expected);
+        assert_eq!(histogram(&reversed), expected);
+    }
+
+    /// Every guess ties against a single candidate, so drawing from one
+    /// generator must not land on the same guess each time.
+    #[test]
+    fn random_ties_are_drawn_from_the_solve_rng() {
+        let config = SolverConfig::<3, Entropy>::default();
+        let problem_set = config.problem_set();
+        let options = SolveOptions {
+            tie_break: TieBreak::Random,
+            ..config.options()
+        };
+        let mut rng = StdRng::seed_from_u64(options.seed);
+
+        let chosen = (0..10)
+            .map(|_| {
+                choose_guess_cached(
+                    &problem_set,
+                    &problem_set[..1],
+                    &Entropy,
+                    &options,
+                    None,
+                    &mut rng,
+                )
+            })
+            .collect_vec();
+
+        assert!(chosen.iter().unique().count() > 1);
+    }
+
+    #[test]
+    fn multi_solver_solves_every_board() {
+        let problem_set = SolverConfig::<3, Entropy>::default().problem_set();
+        let feedback = FeedbackMode::Positional;
+        let secrets = ["rgb", "yyr"].map(|secret| secret.parse::<Password<3>>().unwrap());
+        let mut solver = MultiSolver::new(problem_set.clone(), secrets.len(), feedback);
+
+        // each board is narrowed by its own hint, not by the other's
+        let guess = secrets[0].clone();
+        let hints = secrets.clone().map(|secret| secret.hint(&guess, feedback));
+        assert_ne!(hints[0], hints[1]);
+        solver.apply(&guess, &hints);
+        for (board, hint) in solver.boards().iter().zip(hints) {
+            assert_eq!(
+                **board,
+                filter_candidates(&problem_set, &guess, hint, feedback)
+            );
+        }
+
+        let mut guesses = 1;
+        while let Some(guess) = solver.next_guess() {
+            let hints = secrets.clone().map(|secret| secret.hint(&guess, feedback));
+            solver.apply(&guess, &hints);
+            guesses += 1;
+            assert!(guesses <= problem_set.len(), "the boards never got solved");
+        }
+
+        assert!(solver.is_solved());
+        for (board, secret) in solver.boards().iter().zip(&secrets) {
+            assert_eq!(board.iter().collect_vec(), [secret]);
+        }
+    }
+
+    /// No guess of the problem set can tell the candidates apart, so the
+    /// solve gives up, keeping the guess that didn't help.
+    #[test]
+    fn stalled_guess_ends_the_path() {
+        let parse = |abbrev: &str| abbrev.parse::<Password<3>>().unwrap();
+        let problem_set = [parse("rrr")];
+        let candidates = [parse("rgb"), parse("rbg")];
+        let options = SolverConfig::<3, Entropy>::default().options();
+
+        let path = solve_automatically(
+            &problem_set,
+            &candidates,
+            &mut KnownSecret {
+                secret: candidates[0].clone(),
+                feedback: options.feedback,
+            },
+            &Entropy,
+            options,
+            None,
+        );
+
+        assert_eq!(path.guesses(), 1);
+        assert_eq!(path.steps()[0].guess, problem_set[0]);
+        assert_eq!(path.steps()[0].remaining, 2);
+        assert_eq!(path.solution(), None);
+    }
+}
@@ -0,0 +1,334 @@
+use std::cmp::Ordering;
+
+use crate::{ColorWeights, FeedbackMode, Password};
+
+/// Decides which guess the solver makes next.
+///
+/// Each candidate guess is scored from its partition of the remaining
+/// candidates (see `Password::hint_distribution`) and the solver picks the
+/// guess whose score compares as the greatest under `better`.
+pub trait GuessStrategy<const N: usize>: Sync {
+    fn score(&self, guess: &Password<N>, partition: &[usize]) -> f64;
+
+    /// Scores `guess` against the candidates themselves, for strategies that
+    /// need more than the size of each bucket. `score` of the partition by
+    /// default.
+    fn score_against(
+        &self,
+        guess: &Password<N>,
+        answer_set: &[Password<N>],
+        feedback: FeedbackMode,
+    ) -> f64 {
+        self.score(guess, &guess.hint_distribution(answer_set, feedback))
+    }
+
+    /// `Ordering::Greater` if score `a` is better than score `b`.
+    fn better(a: f64, b: f64) -> Ordering;
+}
+
+/// Scores a hint distribution as computed by `Password::hint_distribution`.
+///
+/// Empty buckets contribute nothing and an empty distribution (no
+/// candidates at all) scores 0.0, so the result is never NaN or infinite.
+pub fn entropy(distribution: &[usize]) -> f64 {
+    let total = distribution.iter().sum::<usize>() as f64;
+    if total == 0.0 {
+        return 0.0;
+    }
+
+    distribution
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| -f64::log2(count as f64 / total))
+        .sum()
+}
+
+/// Maximizes the entropy of the partition.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Entropy;
+
+impl<const N: usize> GuessStrategy<N> for Entropy {
+    fn score(&self, _guess: &Password<N>, partition: &[usize]) -> f64 {
+        entropy(partition)
+    }
+
+    fn better(a: f64, b: f64) -> Ordering {
+        a.total_cmp(&b)
+    }
+}
+
+/// Minimizes the size of the largest bucket, i.e. the worst case.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Minimax;
+
+impl<const N: usize> GuessStrategy<N> for Minimax {
+    fn score(&self, _guess: &Password<N>, partition: &[usize]) -> f64 {
+        partition.iter().copied().max().unwrap_or(0) as f64
+    }
+
+    fn better(a: f64, b: f64) -> Ordering {
+        b.total_cmp(&a)
+    }
+}
+
+/// Minimizes the expected number of candidates left after the guess.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExpectedRemaining;
+
+impl<const N: usize> GuessStrategy<N> for ExpectedRemaining {
+    fn score(&self, _guess: &Password<N>, partition: &[usize]) -> f64 {
+        expected_remaining(partition)
+    }
+
+    fn better(a: f64, b: f64) -> Ordering {
+        b.total_cmp(&a)
+    }
+}
+
+/// Expected size of the bucket the secret falls into, when every candidate
+/// is equally likely. 0.0 for an empty partition.
+pub fn expected_remaining(partition: &[usize]) -> f64 {
+    let total = partition.iter().sum::<usize>() as f64;
+    if total == 0.0 {
+        return 0.0;
+    }
+
+    partition
+        .iter()
+        .map(|&count| (count * count) as f64 / total)
+        .sum()
+}
+
+/// Maximizes entropy while more than `threshold` candidates are left, then
+/// minimizes the largest bucket, where a single unlucky hint costs the most.
+#[derive(Clone, Copy, Debug)]
+pub struct Adaptive {
+    pub threshold: usize,
+}
+
+impl<const N: usize> GuessStrategy<N> for Adaptive {
+    fn score(&self, guess: &Password<N>, partition: &[usize]) -> f64 {
+        // every guess of a step partitions the same candidates, so the two
+        // scores are never compared with each other
+        if partition.iter().sum::<usize>() > self.threshold {
+            Entropy.score(guess, partition)
+        } else {
+            -Minimax.score(guess, partition)
+        }
+    }
+
+    fn better(a: f64, b: f64) -> Ordering {
+        a.total_cmp(&b)
+    }
+}
+
+/// Bits a hint is expected to eliminate, when every candidate is equally
+/// likely: the Shannon entropy of the partition. Unlike `entropy`, buckets
+/// are weighted by their probability, so it is comparable to the realized
+/// `log2(before / after)`. 0.0 for an empty partition.
+pub fn expected_gain(partition: &[usize]) -> f64 {
+    let total = partition.iter().sum::<usize>() as f64;
+    if total == 0.0 {
+        return 0.0;
+    }
+
+    partition
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Minimizes the largest bucket like `Minimax`, breaking its ties by the
+/// greatest entropy and then in favor of guesses that may be the secret.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MinimaxEntropy;
+
+impl MinimaxEntropy {
+    /// Entropy is kept to this many fractional bits, closer ones are tied.
+    const ENTROPY_BITS: i32 = 20;
+    /// Bits below the worst case, holding the entropy and candidate flag.
+    const WORST_CASE_SHIFT: i32 = 35;
+
+    /// Packs (-worst case, entropy, candidate) into one integer that f64
+    /// holds exactly, so comparing scores compares the tuples. That holds
+    /// while the entropy is below 2^14, so it fits in the 35 bits below the
+    /// worst case, and the worst case is below 2^18 candidates, so the
+    /// whole score fits in the 53 bits of an f64 mantissa.
+    fn pack(partition: &[usize], candidate: bool) -> f64 {
+        let worst_case = partition.iter().copied().max().unwrap_or(0);
+        let entropy = entropy(partition);
+        debug_assert!(
+            entropy < f64::powi(2.0, Self::WORST_CASE_SHIFT - Self::ENTROPY_BITS - 1),
+            "entropy {entropy} overflows the packed score"
+        );
+        debug_assert!(
+            worst_case < 1 << (f64::MANTISSA_DIGITS as i32 - Self::WORST_CASE_SHIFT),
+            "worst case {worst_case} overflows the packed score"
+        );
+
+        let entropy = (entropy * f64::powi(2.0, Self::ENTROPY_BITS)).floor();
+        -(worst_case as f64) * f64::powi(2.0, Self::WORST_CASE_SHIFT)
+            + entropy * 2.0
+            + candidate as u8 as f64
+    }
+}
+
+impl<const N: usize> GuessStrategy<N> for MinimaxEntropy {
+    /// The partition alone doesn't tell whether the guess is a candidate,
+    /// so it is scored as one that isn't.
+    fn score(&self, _guess: &Password<N>, partition: &[usize]) -> f64 {
+        Self::pack(partition, false)
+    }
+
+    fn score_against(
+        &self,
+        guess: &Password<N>,
+        answer_set: &[Password<N>],
+        feedback: FeedbackMode,
+    ) -> f64 {
+        let partition = guess.hint_distribution(answer_set, feedback);
+        Self::pack(&partition, answer_set.contains(guess))
+    }
+
+    fn better(a: f64, b: f64) -> Ordering {
+        a.total_cmp(&b)
+    }
+}
+
+/// Maximizes the expected information of the hint when the secret follows
+/// `weights` rather than being uniformly random: the Shannon entropy of the
+/// probability of each hint.
+#[derive(Clone, Debug)]
+pub struct WeightedEntropy<const N: usize> {
+    pub weights: ColorWeights<N>,
+}
+
+impl<const N: usize> GuessStrategy<N> for WeightedEntropy<N> {
+    /// `expected_gain`, which is the same when every candidate is equally
+    /// likely.
+    fn score(&self, _guess: &Password<N>, partition: &[usize]) -> f64 {
+        expected_gain(partition)
+    }
+
+    fn score_against(
+        &self,
+        guess: &Password<N>,
+        answer_set: &[Password<N>],
+        feedback: FeedbackMode,
+    ) -> f64 {
+        let mut masses = vec![0.0; feedback.hints::<N>()];
+        for answer in answer_set {
+            masses[guess.hint(answer, feedback)] += self.weights.prior(answer);
+        }
+
+        let total = masses.iter().sum::<f64>();
+        if total == 0.0 {
+            return 0.0;
+        }
+
+        masses
+            .iter()
+            .filter(|&&mass| mass > 0.0)
+            .map(|&mass| {
+                let p = mass / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    fn better(a: f64, b: f64) -> Ordering {
+        a.total_cmp(&b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::ValueEnum;
+
+    use super::*;
+
+    #[test]
+    fn empty_sets_score_zero() {
+        assert_eq!(entropy(&[]), 0.0);
+        assert_eq!(expected_remaining(&[]), 0.0);
+        assert_eq!(expected_gain(&[]), 0.0);
+
+        // a partition of no candidates, every bucket empty
+        assert_eq!(entropy(&[0; 6]), 0.0);
+        assert_eq!(expected_remaining(&[0; 6]), 0.0);
+        assert_eq!(expected_gain(&[0; 6]), 0.0);
+    }
+
+    #[test]
+    fn calculate_entropy_of_no_candidates_is_zero() {
+        let guess = "rgbyr".parse::<Password<5>>().unwrap();
+
+        for feedback in FeedbackMode::value_variants() {
+            let (entropy, buckets) = guess.calculate_entropy(&[], *feedback);
+
+            assert_eq!(entropy, 0.0);
+            assert!(buckets.iter().all(Vec::is_empty));
+        }
+    }
+
+    /// Asserts that `S::better` is a total order, as the solver's `max_by`
+    /// needs, over boundary values and `strategy`'s scores of degenerate
+    /// partitions, and that none of those scores is NaN.
+    fn assert_total_order<S: GuessStrategy<5>>(strategy: &S) {
+        let guess = "rgbyr".parse::<Password<5>>().unwrap();
+        let mut scores = vec![
+            0.0,
+            -0.0,
+            1.0,
+            1.0,
+            -1.0,
+            f64::MIN_POSITIVE,
+            f64::MAX,
+            f64::MIN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ];
+        for partition in [&[][..], &[0; 6], &[0, 0, 3, 0, 0, 0], &[1, 4, 2, 0, 0, 1]] {
+            let score = strategy.score(&guess, partition);
+            assert!(!score.is_nan(), "NaN score for {partition:?}");
+            scores.push(score);
+        }
+        for feedback in FeedbackMode::value_variants() {
+            let score = strategy.score_against(&guess, &[], *feedback);
+            assert!(!score.is_nan(), "NaN score against no candidates");
+            scores.push(score);
+        }
+
+        for &a in &scores {
+            assert_eq!(
+                S::better(a, a),
+                Ordering::Equal,
+                "{a} isn't equal to itself"
+            );
+            for &b in &scores {
+                assert_eq!(S::better(a, b), S::better(b, a).reverse(), "{a} and {b}");
+                for &c in &scores {
+                    if S::better(a, b) != Ordering::Less && S::better(b, c) != Ordering::Less {
+                        assert_ne!(S::better(a, c), Ordering::Less, "{a}, {b} and {c}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn better_is_a_total_order() {
+        assert_total_order(&Entropy);
+        assert_total_order(&Minimax);
+        assert_total_order(&ExpectedRemaining);
+        assert_total_order(&MinimaxEntropy);
+        assert_total_order(&Adaptive { threshold: 16 });
+        assert_total_order(&WeightedEntropy {
+            weights: ColorWeights::uniform(),
+        });
+    }
+}
@@ -0,0 +1,218 @@
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use derive_more::Display;
+
+use crate::{FeedbackMode, Password, SolvePath};
+
+#[derive(Debug, Display)]
+#[display(fmt = "invalid transcript: {}", _0)]
+pub struct TranscriptError(String);
+
+impl std::error::Error for TranscriptError {}
+
+/// A whole game: the secret and every guess made with the hint it got.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transcript<const N: usize> {
+    pub secret: Password<N>,
+    pub feedback: FeedbackMode,
+    pub moves: Vec<(Password<N>, usize)>,
+}
+
+/// A recorded hint that differs from the one the secret gives.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Discrepancy<const N: usize> {
+    /// Index of the move in `Transcript::moves`.
+    pub turn: usize,
+    pub guess: Password<N>,
+    pub recorded: usize,
+    pub actual: usize,
+}
+
+impl<const N: usize> Transcript<N> {
+    /// The game `path` played against `secret`, ending with the guess of
+    /// the deduced solution if the solver didn't already make it.
+    pub fn from_solve(secret: Password<N>, path: &SolvePath<N>) -> Self {
+        let feedback = path.feedback();
+        let mut moves = path
+            .steps()
+            .iter()
+            .map(|step| (step.guess.clone(), step.hint))
+            .collect::<Vec<_>>();
+
+        let solved = feedback.solved::<N>();
+        if let Some(solution) = path.solution() {
+            if moves.last().is_none_or(|(_, hint)| *hint != solved) {
+                moves.push((solution.clone(), solved));
+            }
+        }
+
+        Self {
+            secret,
+            feedback,
+            moves,
+        }
+    }
+
+    /// Gives every recorded guess again, returning the moves whose hint
+    /// isn't the one the secret gives.
+    pub fn replay(&self) -> Vec<Discrepancy<N>> {
+        self.moves
+            .iter()
+            .enumerate()
+            .filter_map(|(turn, (guess, recorded))| {
+                let actual = self.secret.hint(guess, self.feedback);
+                (actual != *recorded).then(|| Discrepancy {
+                    turn,
+                    guess: guess.clone(),
+                    recorded: *recorded,
+                    actual,
+                })
+            })
+            .collect()
+    }
+
+    /// Whether the last guess is the secret.
+    pub fn is_solved(&self) -> bool {
+        self.moves
+            .last()
+            .is_some_and(|(guess, _)| *guess == self.secret)
+    }
+}
+
+/// A `secret <abbrev>` and a `feedback <mode>` line followed by a
+/// `guess <abbrev> <hint>` line per move, with hints as typed by a player.
+impl<const N: usize> std::fmt::Display for Transcript<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "secret {}", self.secret.abbrev())?;
+        writeln!(
+            f,
+            "feedback {}",
+            self.feedback.to_possible_value().unwrap().get_name()
+        )?;
+
+        for (guess, hint) in &self.moves {
+            writeln!(
+                f,
+                "guess {} {}",
+                guess.abbrev(),
+                self.feedback.format_hint::<N>(*hint)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<const N: usize> FromStr for Transcript<N> {
+    type Err = TranscriptError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut secret = None;
+        let mut feedback = FeedbackMode::default();
+        let mut moves = vec![];
+
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let invalid = |err: String| TranscriptError(format!("{err} in line '{line}'"));
+
+            match line.split_whitespace().collect::<Vec<_>>()[..] {
+                ["secret", password] => {
+                    secret = Some(password.parse().map_err(|err| invalid(format!("{err}")))?);
+                }
+                // the guesses before it would have been read in another mode
+                ["feedback", _] if !moves.is_empty() => {
+                    return Err(invalid("feedback after the first guess".to_string()))
+                }
+                ["feedback", mode] => {
+                    feedback = FeedbackMode::from_str(mode, true).map_err(invalid)?
+                }
+                ["guess", guess, hint] => moves.push((
+                    guess.parse().map_err(|err| invalid(format!("{err}")))?,
+                    feedback.parse_hint::<N>(hint).map_err(invalid)?,
+                )),
+                _ => return Err(TranscriptError(format!("unexpected line '{line}'"))),
+            }
+        }
+
+        Ok(Self {
+            secret: secret.ok_or(TranscriptError("no secret given".to_string()))?,
+            feedback,
+            moves,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{solve_automatically, Entropy, KnownSecret, SolveOptions, SolverConfig};
+
+    /// A full-feedback solve of `secret`, so that hints aren't plain counts.
+    fn record(secret: &str) -> Transcript<4> {
+        let config = SolverConfig::<4, Entropy>::default();
+        let problem_set = config.problem_set();
+        let options = SolveOptions {
+            feedback: FeedbackMode::Full,
+            ..config.options()
+        };
+        let secret = secret.parse::<Password<4>>().unwrap();
+
+        let path = solve_automatically(
+            &problem_set,
+            &problem_set,
+            &mut KnownSecret {
+                secret: secret.clone(),
+                feedback: options.feedback,
+            },
+            &Entropy,
+            options,
+            None,
+        );
+
+        Transcript::from_solve(secret, &path)
+    }
+
+    #[test]
+    fn recorded_solve_round_trips_and_replays_cleanly() {
+        let transcript = record("ybgr");
+        let parsed = transcript.to_string().parse::<Transcript<4>>().unwrap();
+
+        assert_eq!(parsed, transcript);
+        assert!(parsed.moves.len() > 1);
+        assert!(parsed.is_solved());
+        assert_eq!(parsed.replay(), vec![]);
+    }
+
+    #[test]
+    fn tampered_hint_is_reported() {
+        let mut transcript = record("ybgr");
+        let (guess, actual) = transcript.moves[0].clone();
+        let recorded = (0..FeedbackMode::Full.hints::<4>())
+            .find(|&hint| hint != actual)
+            .unwrap();
+        transcript.moves[0].1 = recorded;
+
+        let parsed = transcript.to_string().parse::<Transcript<4>>().unwrap();
+        assert_eq!(
+            parsed.replay(),
+            vec![Discrepancy {
+                turn: 0,
+                guess,
+                recorded,
+                actual,
+            }]
+        );
+    }
+
+    #[test]
+    fn feedback_after_a_guess_is_rejected() {
+        let err = "secret rgby\nguess rrrr 1\nfeedback full\n"
+            .parse::<Transcript<4>>()
+            .unwrap_err();
+
+        assert!(err.to_string().contains("feedback after the first guess"));
+        assert!("secret rgby\nfeedback full\nguess rrrr 1/0\n"
+            .parse::<Transcript<4>>()
+            .is_ok());
+    }
+}
@@ -0,0 +1,191 @@
+use itertools::Itertools;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use crate::{choose_guess, FeedbackMode, GuessStrategy, Password, SolveOptions};
+
+/// Every guess a strategy makes, for every possible secret.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecisionTree<const N: usize> {
+    /// Only one candidate is left, so no further guess is needed.
+    Leaf(Password<N>),
+    Node {
+        guess: Password<N>,
+        /// Subtree for every hint that at least one candidate gives.
+        children: Vec<(usize, DecisionTree<N>)>,
+    },
+}
+
+impl<const N: usize> DecisionTree<N> {
+    /// Follows `strategy` down every hint branch, starting from `candidates`.
+    /// `None` if there are no candidates.
+    pub fn build<S: GuessStrategy<N>>(
+        problem_set: &[Password<N>],
+        candidates: &[Password<N>],
+        strategy: &S,
+        options: &SolveOptions,
+    ) -> Option<Self> {
+        match candidates {
+            [] => None,
+            [candidate] => Some(Self::Leaf(candidate.clone())),
+            _ => {
+                let guess = choose_guess(problem_set, candidates, strategy, options);
+                Some(Self::node(
+                    problem_set,
+                    candidates,
+                    guess,
+                    strategy,
+                    options,
+                ))
+            }
+        }
+    }
+
+    /// Like `build`, but guesses `opener` first whenever more than one
+    /// candidate is left, and only follows `strategy` from the second guess.
+    pub fn build_with_opener<S: GuessStrategy<N>>(
+        problem_set: &[Password<N>],
+        candidates: &[Password<N>],
+        opener: &Password<N>,
+        strategy: &S,
+        options: &SolveOptions,
+    ) -> Option<Self> {
+        match candidates {
+            [] | [_] => Self::build(problem_set, candidates, strategy, options),
+            _ => Some(Self::node(
+                problem_set,
+                candidates,
+                opener.clone(),
+                strategy,
+                options,
+            )),
+        }
+    }
+
+    /// The node guessing `guess` against `candidates`, with a subtree built
+    /// by `strategy` for every hint.
+    fn node<S: GuessStrategy<N>>(
+        problem_set: &[Password<N>],
+        candidates: &[Password<N>],
+        guess: Password<N>,
+        strategy: &S,
+        options: &SolveOptions,
+    ) -> Self {
+        let (_, buckets) = guess.calculate_entropy(candidates, options.feedback);
+
+        let children = buckets
+            .into_iter()
+            .enumerate()
+            .filter(|(_, bucket)| !bucket.is_empty())
+            .map(|(hint, bucket)| (hint, bucket.into_iter().cloned().collect::<Vec<_>>()))
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|(hint, bucket)| {
+                let child = Self::build(problem_set, &bucket, strategy, options);
+                (hint, child.unwrap())
+            })
+            .collect();
+
+        Self::Node { guess, children }
+    }
+
+    /// Number of guesses made before each secret is the only candidate left,
+    /// the same count `solve_automatically` reports.
+    pub fn depths(&self) -> Vec<usize> {
+        match self {
+            Self::Leaf(_) => vec![0],
+            Self::Node { children, .. } => children
+                .iter()
+                .flat_map(|(_, child)| child.depths())
+                .map(|depth| depth + 1)
+                .collect(),
+        }
+    }
+
+    /// The guaranteed worst case of the strategy: the longest path to a leaf.
+    pub fn max_depth(&self) -> usize {
+        match self {
+            Self::Leaf(_) => 0,
+            Self::Node { children, .. } => {
+                1 + children
+                    .iter()
+                    .map(|(_, child)| child.max_depth())
+                    .max()
+                    .unwrap_or(0)
+            }
+        }
+    }
+
+    /// Fraction of secrets that are the only candidate left after at most
+    /// `guesses` guesses, i.e. whose leaf is at most that deep.
+    pub fn solved_within(&self, guesses: usize) -> f64 {
+        let depths = self.depths();
+
+        depths.iter().filter(|&&depth| depth <= guesses).count() as f64 / depths.len() as f64
+    }
+
+    /// Average path length over every secret.
+    pub fn average_depth(&self) -> f64 {
+        let depths = self.depths();
+
+        depths.iter().sum::<usize>() as f64 / depths.len() as f64
+    }
+
+    /// Graphviz DOT source of the tree: a node per guess or solution, and an
+    /// edge per hint labeled with its decoded `feedback`.
+    pub fn to_dot(&self, feedback: FeedbackMode) -> String {
+        let mut dot = String::from("digraph decision_tree {\n");
+        self.write_dot(feedback, &mut dot, &mut 0);
+        dot.push_str("}\n");
+
+        dot
+    }
+
+    /// Writes this subtree with ids from `next_id` on, returning its root's.
+    fn write_dot(&self, feedback: FeedbackMode, dot: &mut String, next_id: &mut usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+
+        match self {
+            Self::Leaf(solution) => {
+                dot.push_str(&format!(
+                    "  n{id} [label=\"{}\", shape=box];\n",
+                    solution.abbrev()
+                ));
+            }
+            Self::Node { guess, children } => {
+                dot.push_str(&format!("  n{id} [label=\"{}\"];\n", guess.abbrev()));
+                for (hint, child) in children {
+                    let child_id = child.write_dot(feedback, dot, next_id);
+                    dot.push_str(&format!(
+                        "  n{id} -> n{child_id} [label=\"{}\"];\n",
+                        feedback.decode::<N>(*hint)
+                    ));
+                }
+            }
+        }
+
+        id
+    }
+
+    /// The tree as JSON: `{"solution": ...}` for a leaf, and
+    /// `{"guess": ..., "children": [{"hint": ..., "tree": ...}]}` otherwise,
+    /// with passwords as abbreviations and hints as indices.
+    pub fn to_json(&self) -> String {
+        match self {
+            Self::Leaf(solution) => format!("{{\"solution\":\"{}\"}}", solution.abbrev()),
+            Self::Node { guess, children } => {
+                let children = children
+                    .iter()
+                    .map(|(hint, child)| {
+                        format!("{{\"hint\":{hint},\"tree\":{}}}", child.to_json())
+                    })
+                    .join(",");
+
+                format!(
+                    "{{\"guess\":\"{}\",\"children\":[{children}]}}",
+                    guess.abbrev()
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::str::FromStr;
+
+use itertools::Itertools;
+use rand::Rng;
+
+use crate::{Color, Password, PasswordError, COLORS};
+
+/// Probability of every color at each position of a generated password.
+///
+/// Each row follows the order of `Color::all()` and is normalized
+/// independently, so `"4,1,1,1;..."` makes Red four times as likely as any
+/// other color in the first position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorWeights<const N: usize> {
+    rows: [[f64; COLORS]; N],
+}
+
+impl<const N: usize> ColorWeights<{ N }> {
+    /// Every color equally likely in every position.
+    pub fn uniform() -> Self {
+        Self {
+            rows: [[1.0 / COLORS as f64; COLORS]; N],
+        }
+    }
+
+    /// Normalizes each row of `rows`, which must hold finite non-negative
+    /// weights with a positive sum.
+    pub fn new(mut rows: [[f64; COLORS]; N]) -> Result<Self, PasswordError> {
+        for (position, row) in rows.iter_mut().enumerate() {
+            if row
+                .iter()
+                .any(|weight| !weight.is_finite() || *weight < 0.0)
+            {
+                return Err(PasswordError::InvalidWeights(format!(
+                    "position {} has a negative or non-finite weight",
+                    position + 1
+                )));
+            }
+
+            let total = row.iter().sum::<f64>();
+            if total <= 0.0 {
+                return Err(PasswordError::InvalidWeights(format!(
+                    "position {} has no positive weight",
+                    position + 1
+                )));
+            }
+
+            row.iter_mut().for_each(|weight| *weight /= total);
+        }
+
+        Ok(Self { rows })
+    }
+
+    /// The normalized probability of `color` at `position`.
+    pub fn probability(&self, position: usize, color: Color) -> f64 {
+        self.rows[position][color.index() - 1]
+    }
+
+    /// Probability of `password` being generated, colors being drawn
+    /// independently at every position.
+    pub fn prior(&self, password: &Password<N>) -> f64 {
+        (0..N)
+            .map(|position| self.probability(position, password[position]))
+            .product()
+    }
+
+    pub fn sample<R: Rng + ?Sized>(&self, position: usize, rng: &mut R) -> Color {
+        let mut result = rng.gen_range(0.0..1.0);
+
+        for (color, weight) in Color::all().into_iter().zip(self.rows[position]) {
+            if result < weight {
+                return color;
+            }
+            result -= weight;
+        }
+
+        // only reachable through rounding, fall back to the last likely color
+        Color::all()
+            .into_iter()
+            .zip(self.rows[position])
+            .filter(|(_, weight)| *weight > 0.0)
+            .map(|(color, _)| color)
+            .next_back()
+            .unwrap()
+    }
+}
+
+impl<const N: usize> Default for ColorWeights<N> {
+    fn default() -> Self {
+        Self::uniform()
+    }
+}
+
+/// Parses one row of `COLORS` comma-separated weights per position, rows
+/// separated by `;`.
+impl<const N: usize> FromStr for ColorWeights<N> {
+    type Err = PasswordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = s.trim().split(';').collect_vec();
+        if rows.len() != N {
+            return Err(PasswordError::WrongLength {
+                expected: N,
+                found: rows.len(),
+            });
+        }
+
+        let mut weights = [[0.0; COLORS]; N];
+        for (parsed, row) in weights.iter_mut().zip(rows) {
+            let values = row
+                .split(',')
+                .map(|value| {
+                    value.trim().parse::<f64>().map_err(|_| {
+                        PasswordError::InvalidWeights(format!("'{}' is not a number", value.trim()))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            *parsed = values.try_into().map_err(|values: Vec<f64>| {
+                PasswordError::InvalidWeights(format!(
+                    "expected {COLORS} weights per position, found {}",
+                    values.len()
+                ))
+            })?;
+        }
+
+        Self::new(weights)
+    }
+}